@@ -0,0 +1,246 @@
+// Barnes–Hut octree for the self-gravity of the fluid.
+//
+// All particles carry the same gravitating mass, so a cell is summarized
+// by its particle count and center of mass. A cell whose size seen from
+// the evaluation point is below the opening angle acts as a point mass,
+// otherwise its children are visited; leaves are summed directly. Forces
+// use Plummer softening, 1 / (r^2 + softening^2) instead of 1 / r^2, which
+// keeps close pairs finite and makes a particle's pull on itself vanish.
+//
+// Nodes live in flat typed arrays that are reused between builds.
+
+// Beyond this depth coincident particles share a leaf instead of splitting
+const MAX_DEPTH = 24;
+
+export class BarnesHutTree {
+    constructor() {
+        this.positions = null;
+        this.particleMass = 0;
+        this.nodeCount = 0;
+        this.allocateNodes(64);
+        this.next = new Int32Array(0);    // Linked list of the particles in each leaf
+        this.stack = new Int32Array(64);
+    }
+
+    allocateNodes(capacity) {
+        const grow = (Type, size, old) => {
+            const array = new Type(size);
+            if (old) array.set(old.subarray(0, Math.min(old.length, size)));
+            return array;
+        };
+        this.capacity = capacity;
+        this.centers = grow(Float64Array, capacity * 3, this.centers);   // Geometric center of each cube
+        this.halfSizes = grow(Float64Array, capacity, this.halfSizes);
+        this.depths = grow(Int32Array, capacity, this.depths);
+        this.children = grow(Int32Array, capacity * 8, this.children);   // -1 where there is no child
+        this.first = grow(Int32Array, capacity, this.first);             // First particle of a leaf, -1 for inner nodes
+        this.counts = grow(Int32Array, capacity, this.counts);
+        this.moments = grow(Float64Array, capacity * 3, this.moments);   // Center of mass once built
+    }
+
+    addNode(x, y, z, halfSize, depth) {
+        if (this.nodeCount === this.capacity) {
+            this.allocateNodes(this.capacity * 2);
+        }
+        const node = this.nodeCount++;
+        this.centers[node * 3] = x;
+        this.centers[node * 3 + 1] = y;
+        this.centers[node * 3 + 2] = z;
+        this.halfSizes[node] = halfSize;
+        this.depths[node] = depth;
+        this.children.fill(-1, node * 8, node * 8 + 8);
+        this.first[node] = -1;
+        this.counts[node] = 0;
+        return node;
+    }
+
+    // Builds the tree over `count` particles of a flat [x0, y0, z0, ...]
+    // array, each of gravitating mass `particleMass`
+    build(positions, count, particleMass) {
+        this.positions = positions;
+        this.particleMass = particleMass;
+        this.nodeCount = 0;
+        if (this.next.length < count) {
+            this.next = new Int32Array(count);
+        }
+
+        let minX = Infinity, minY = Infinity, minZ = Infinity;
+        let maxX = -Infinity, maxY = -Infinity, maxZ = -Infinity;
+        for (let k = 0; k < count * 3; k += 3) {
+            minX = Math.min(minX, positions[k]);
+            minY = Math.min(minY, positions[k + 1]);
+            minZ = Math.min(minZ, positions[k + 2]);
+            maxX = Math.max(maxX, positions[k]);
+            maxY = Math.max(maxY, positions[k + 1]);
+            maxZ = Math.max(maxZ, positions[k + 2]);
+        }
+        if (count === 0) {
+            minX = minY = minZ = maxX = maxY = maxZ = 0;
+        }
+        const halfSize = 0.5 * Math.max(maxX - minX, maxY - minY, maxZ - minZ) * 1.0001 + 1e-9;
+        this.addNode(0.5 * (minX + maxX), 0.5 * (minY + maxY), 0.5 * (minZ + maxZ), halfSize, 0);
+
+        for (let i = 0; i < count; i++) {
+            this.insert(i);
+        }
+        this.computeMoments();
+        return this;
+    }
+
+    insert(i) {
+        const positions = this.positions;
+        let node = 0;
+        for (;;) {
+            this.counts[node]++;
+            const isLeaf = this.first[node] >= 0 || this.isEmpty(node);
+            if (isLeaf) {
+                if (this.first[node] < 0 || this.depths[node] >= MAX_DEPTH) {
+                    this.next[i] = this.first[node];
+                    this.first[node] = i;
+                    return;
+                }
+                // Occupied leaf: push its particles one level down first
+                let j = this.first[node];
+                this.first[node] = -1;
+                while (j >= 0) {
+                    const following = this.next[j];
+                    this.pushDown(node, j);
+                    j = following;
+                }
+            }
+            node = this.childFor(node, positions[i * 3], positions[i * 3 + 1], positions[i * 3 + 2]);
+        }
+    }
+
+    isEmpty(node) {
+        for (let c = 0; c < 8; c++) {
+            if (this.children[node * 8 + c] >= 0) return false;
+        }
+        return true;
+    }
+
+    // Moves particle j from a leaf that is being split into a fresh child
+    pushDown(node, j) {
+        const child = this.childFor(node, this.positions[j * 3], this.positions[j * 3 + 1], this.positions[j * 3 + 2]);
+        this.counts[child]++;
+        this.next[j] = this.first[child];
+        this.first[child] = j;
+    }
+
+    // The child octant of `node` containing the point, created on demand
+    childFor(node, x, y, z) {
+        const cx = this.centers[node * 3];
+        const cy = this.centers[node * 3 + 1];
+        const cz = this.centers[node * 3 + 2];
+        const octant = (x >= cx ? 1 : 0) | (y >= cy ? 2 : 0) | (z >= cz ? 4 : 0);
+        let child = this.children[node * 8 + octant];
+        if (child < 0) {
+            const quarter = 0.5 * this.halfSizes[node];
+            child = this.addNode(
+                cx + (octant & 1 ? quarter : -quarter),
+                cy + (octant & 2 ? quarter : -quarter),
+                cz + (octant & 4 ? quarter : -quarter),
+                quarter, this.depths[node] + 1);
+            this.children[node * 8 + octant] = child;
+        }
+        return child;
+    }
+
+    // Centers of mass, children first: they always come after their parent
+    computeMoments() {
+        const positions = this.positions;
+        for (let node = this.nodeCount - 1; node >= 0; node--) {
+            let sx = 0, sy = 0, sz = 0;
+            if (this.first[node] >= 0) {
+                for (let j = this.first[node]; j >= 0; j = this.next[j]) {
+                    sx += positions[j * 3];
+                    sy += positions[j * 3 + 1];
+                    sz += positions[j * 3 + 2];
+                }
+            } else {
+                for (let c = 0; c < 8; c++) {
+                    const child = this.children[node * 8 + c];
+                    if (child < 0) continue;
+                    const weight = this.counts[child];
+                    sx += this.moments[child * 3] * weight;
+                    sy += this.moments[child * 3 + 1] * weight;
+                    sz += this.moments[child * 3 + 2] * weight;
+                }
+            }
+            const count = this.counts[node] || 1;
+            this.moments[node * 3] = sx / count;
+            this.moments[node * 3 + 1] = sy / count;
+            this.moments[node * 3 + 2] = sz / count;
+        }
+    }
+
+    // Adds the pull of the tree at a point to `out` (without the
+    // gravitational constant) and returns the potential there. The point's
+    // own particle, if any, contributes no force but -m / softening to the
+    // potential.
+    accelerationAt(x, y, z, openingAngle, softening, out) {
+        if (this.nodeCount === 0 || this.counts[0] === 0) return 0;
+
+        const positions = this.positions;
+        const softeningSq = softening * softening;
+        const openingSq = openingAngle * openingAngle;
+        let potential = 0;
+        let ax = 0, ay = 0, az = 0;
+
+        let top = 0;
+        this.stack[top++] = 0;
+        while (top > 0) {
+            const node = this.stack[--top];
+
+            if (this.first[node] >= 0) {
+                for (let j = this.first[node]; j >= 0; j = this.next[j]) {
+                    const dx = positions[j * 3] - x;
+                    const dy = positions[j * 3 + 1] - y;
+                    const dz = positions[j * 3 + 2] - z;
+                    const inverse = 1 / Math.sqrt(dx * dx + dy * dy + dz * dz + softeningSq);
+                    const scale = this.particleMass * inverse * inverse * inverse;
+                    ax += dx * scale;
+                    ay += dy * scale;
+                    az += dz * scale;
+                    potential -= this.particleMass * inverse;
+                }
+                continue;
+            }
+
+            const dx = this.moments[node * 3] - x;
+            const dy = this.moments[node * 3 + 1] - y;
+            const dz = this.moments[node * 3 + 2] - z;
+            const distanceSq = dx * dx + dy * dy + dz * dz;
+            const size = 2 * this.halfSizes[node];
+
+            if (size * size < openingSq * distanceSq) {
+                // Far enough away to act as a single point mass
+                const mass = this.particleMass * this.counts[node];
+                const inverse = 1 / Math.sqrt(distanceSq + softeningSq);
+                const scale = mass * inverse * inverse * inverse;
+                ax += dx * scale;
+                ay += dy * scale;
+                az += dz * scale;
+                potential -= mass * inverse;
+                continue;
+            }
+
+            if (top + 8 > this.stack.length) {
+                const stack = new Int32Array(this.stack.length * 2);
+                stack.set(this.stack);
+                this.stack = stack;
+            }
+            for (let c = 0; c < 8; c++) {
+                const child = this.children[node * 8 + c];
+                if (child >= 0) {
+                    this.stack[top++] = child;
+                }
+            }
+        }
+
+        out.x += ax;
+        out.y += ay;
+        out.z += az;
+        return potential;
+    }
+}
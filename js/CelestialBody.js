@@ -0,0 +1,128 @@
+import { Vector3 } from './Vector3.js';
+
+export const BODY_TYPES = ['planet', 'moon', 'sun'];
+
+const DEFAULT_COLORS = {
+    planet: 0x44aa44,
+    moon: 0x800080,
+    sun: 0xffdd55
+};
+
+function toVector(value) {
+    if (Array.isArray(value)) {
+        return new Vector3(value[0] || 0, value[1] || 0, value[2] || 0);
+    }
+    if (value && typeof value === 'object') {
+        return new Vector3(value.x || 0, value.y || 0, value.z || 0);
+    }
+    return new Vector3();
+}
+
+function toColor(value, fallback) {
+    if (typeof value === 'string') {
+        return parseInt(value.replace('#', ''), 16);
+    }
+    return typeof value === 'number' ? value : fallback;
+}
+
+// A massive body (planet, moon or sun) built from a scenario object entry:
+//
+//   {
+//     id: 'moon', type: 'moon', mass: 100, radius: 1, color: '#800080',
+//     rotationSpeed: 0, gravityMultiplier: 1,
+//     obliquity: 0, axisAzimuth: 0,     // spin axis tilt from +Y and its direction
+//     restitution: 0.1, friction: 0.3,  // optional, else the surface parameters
+//     tidal: false,                     // only tidal pull on the fluid in Kepler mode (default for suns)
+//     fluid: false,                     // the body the ocean is placed on
+//     position: [x, y, z], velocity: [x, y, z],   // used when there's no orbit
+//     orbit: { parent: 'planet', semiMajorAxis: 15, eccentricity: 0,
+//              inclination: 0, argumentOfPeriapsis: 0, ascendingNode: 0,
+//              meanAnomaly: 0, meanMotion: 0.5 }   // meanMotion is optional
+//   }
+//
+// Orbits are relative to the parent body, which must appear earlier in the
+// list. Without an explicit meanMotion the Keplerian rate for the two masses
+// is used.
+//
+// gravityMultiplier strengthens the body's pull on the fluid only, to make
+// tides visible at toy scale. Gravity between bodies, N-body orbits and the
+// barycentric setup always use the plain mass.
+export class CelestialBody {
+    constructor(definition) {
+        this.id = definition.id;
+        this.type = BODY_TYPES.includes(definition.type) ? definition.type : 'planet';
+        this.mass = definition.mass;
+        this.radius = definition.radius;
+        this.color = toColor(definition.color, DEFAULT_COLORS[this.type]);
+        this.rotationSpeed = definition.rotationSpeed || 0;
+        this.rotationAngle = definition.rotationAngle || 0;
+
+        // The body spins about +Y tilted by `obliquity` towards the
+        // direction `axisAzimuth` in the XZ plane
+        this.obliquity = definition.obliquity || 0;
+        this.axisAzimuth = definition.axisAzimuth || 0;
+        this.spinAxis = new Vector3();
+        this.updateSpinAxis();
+        this.gravityMultiplier = definition.gravityMultiplier ?? 1.0;
+        this.hostsFluid = Boolean(definition.fluid);
+        this.tidal = definition.tidal ?? this.type === 'sun';
+        this.restitution = definition.restitution ?? null;
+        this.friction = definition.friction ?? null;
+
+        this.orbit = null;
+        if (definition.orbit) {
+            const orbit = definition.orbit;
+            this.orbit = {
+                parent: orbit.parent,
+                semiMajorAxis: orbit.semiMajorAxis,
+                eccentricity: orbit.eccentricity || 0,
+                inclination: orbit.inclination || 0,
+                argumentOfPeriapsis: orbit.argumentOfPeriapsis || 0,
+                ascendingNode: orbit.ascendingNode || 0,
+                initialMeanAnomaly: orbit.meanAnomaly || 0,
+                meanAnomaly: orbit.meanAnomaly || 0,
+                meanMotion: orbit.meanMotion ?? null
+            };
+        }
+
+        this.initialPosition = toVector(definition.position);
+        this.initialVelocity = toVector(definition.velocity);
+
+        // Dynamic state
+        this.position = this.initialPosition.clone();
+        this.velocity = this.initialVelocity.clone();
+        this.acceleration = new Vector3();
+
+        // Resolved by the simulator
+        this.parent = null;
+        this.mesh = null;
+        this.orbitLine = null;
+    }
+
+    updateSpinAxis() {
+        const tilt = Math.sin(this.obliquity);
+        this.spinAxis.set(tilt * Math.cos(this.axisAzimuth), Math.cos(this.obliquity), tilt * Math.sin(this.axisAzimuth));
+    }
+
+    // World direction of the surface point at a body-fixed latitude and
+    // longitude (radians), following the body's tilt and spin the same way
+    // its mesh does
+    surfaceDirection(latitude, longitude, out = new Vector3()) {
+        out.set(Math.cos(latitude) * Math.cos(longitude), Math.sin(latitude), -Math.cos(latitude) * Math.sin(longitude));
+        if (this.obliquity) {
+            const tiltAxis = new Vector3(Math.sin(this.axisAzimuth), 0, -Math.cos(this.axisAzimuth));
+            out.applyAxisAngle(tiltAxis, this.obliquity);
+        }
+        return out.applyAxisAngle(this.spinAxis, this.rotationAngle);
+    }
+
+    getOrbitElements() {
+        return this.orbit && {
+            semiMajorAxis: this.orbit.semiMajorAxis,
+            eccentricity: this.orbit.eccentricity,
+            inclination: this.orbit.inclination,
+            argumentOfPeriapsis: this.orbit.argumentOfPeriapsis,
+            ascendingNode: this.orbit.ascendingNode
+        };
+    }
+}
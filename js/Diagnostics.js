@@ -0,0 +1,225 @@
+// Scalar measurements of a SimulationCore for logging and analysis.
+//
+// Energies are for the fluid only, in the inertial frame: kinetic energy of
+// the particles plus their (softened) gravitational potential energy in the
+// field of every body and, with self-gravity, of the fluid itself. Tidal
+// bodies in Kepler mode contribute their tidal potential, matching the
+// force the particles feel. Sea level and bulge are measured relative to the
+// primary body, the bulge against the direction of the first moon. A
+// particle has escaped once it is no longer bound to the primary body.
+
+import { Vector3 } from 'three';
+import { BarnesHutTree } from './BarnesHut.js';
+
+// Scratch state reused by every measurement, so sampling a running
+// simulation doesn't allocate a tree each time or touch the core's own
+const tree = new BarnesHutTree();
+const reference = new Vector3();
+
+// Second Legendre polynomial, the shape of an equilibrium tidal bulge
+function legendreP2(cosine) {
+    return 0.5 * (3 * cosine * cosine - 1);
+}
+
+export function measureDiagnostics(core) {
+    const { count, positions, velocities } = core.particles;
+    const mass = core.particleMass;
+    const primary = core.primaryBody;
+    const moon = core.primaryMoon;
+    const scaledG = core.scaledGravitationalConstant();
+
+    let kineticEnergy = 0;
+    let potentialEnergy = 0;
+    let radiusSum = 0;
+    let maxRadius = primary.radius;
+    let escapedCount = 0;
+    const hostGravity = scaledG * primary.mass * primary.gravityMultiplier;
+
+    // The uniform pull subtracted for tidal bodies has potential
+    // reference · (r - host center)
+    core.computeTidalReference(reference);
+
+    for (let k = 0; k < count * 3; k += 3) {
+        kineticEnergy += 0.5 * mass * (velocities[k] * velocities[k] +
+            velocities[k + 1] * velocities[k + 1] + velocities[k + 2] * velocities[k + 2]);
+        for (const body of core.bodies) {
+            // Same 0.5 softening as the gravity the particles feel
+            const distance = Math.max(Math.hypot(positions[k] - body.position.x,
+                positions[k + 1] - body.position.y, positions[k + 2] - body.position.z), 0.5);
+            potentialEnergy -= scaledG * body.mass * body.gravityMultiplier * mass / distance;
+        }
+        potentialEnergy += mass * (reference.x * (positions[k] - primary.position.x) +
+            reference.y * (positions[k + 1] - primary.position.y) +
+            reference.z * (positions[k + 2] - primary.position.z));
+        const radius = Math.hypot(positions[k] - primary.position.x,
+            positions[k + 1] - primary.position.y, positions[k + 2] - primary.position.z);
+        radiusSum += radius;
+        maxRadius = Math.max(maxRadius, radius);
+
+        const relativeSpeedSq = (velocities[k] - primary.velocity.x) ** 2 +
+            (velocities[k + 1] - primary.velocity.y) ** 2 + (velocities[k + 2] - primary.velocity.z) ** 2;
+        if (0.5 * relativeSpeedSq > hostGravity / Math.max(radius, 0.5)) {
+            escapedCount++;
+        }
+    }
+
+    if (core.parameters.selfGravity && count > 0) {
+        // Pair energy from the same tree approximation the particles feel,
+        // each pair counted once and the softened self terms removed
+        const { selfGravityOpeningAngle, selfGravitySoftening } = core.parameters;
+        const gravityMass = core.particleGravityMass();
+        tree.build(positions, count, gravityMass);
+        const scratch = { x: 0, y: 0, z: 0 };
+        let potentialSum = 0;
+        for (let k = 0; k < count * 3; k += 3) {
+            potentialSum += tree.accelerationAt(positions[k], positions[k + 1], positions[k + 2],
+                selfGravityOpeningAngle, selfGravitySoftening, scratch);
+            potentialSum += gravityMass / selfGravitySoftening;
+        }
+        potentialEnergy += 0.5 * scaledG * mass * potentialSum;
+    }
+
+    const meanRadius = count > 0 ? radiusSum / count : primary.radius;
+
+    // Least-squares fit of r = meanRadius + A * P2(cos angle to the moon);
+    // A is the height of the sub-moon bulge above the mean surface.
+    //
+    // The lag comes from a second fit in the moon's orbital plane, with x
+    // towards the moon and y along its motion: r - meanRadius =
+    // a * (x^2 - y^2) / r^2 + b * 2xy / r^2 puts the bulge axis at
+    // atan2(b, a) / 2 from the moon, positive when the bulge runs ahead.
+    let bulgeAmplitude = 0;
+    let bulgeLagAngle = 0;
+    if (moon && count > 0) {
+        const mx = moon.position.x - primary.position.x;
+        const my = moon.position.y - primary.position.y;
+        const mz = moon.position.z - primary.position.z;
+        const moonDistance = Math.hypot(mx, my, mz) || 1;
+        const ux = mx / moonDistance;
+        const uy = my / moonDistance;
+        const uz = mz / moonDistance;
+
+        // Direction of motion across the line of sight, or any
+        // perpendicular if the moon is at rest relative to the primary
+        let tx = moon.velocity.x - primary.velocity.x;
+        let ty = moon.velocity.y - primary.velocity.y;
+        let tz = moon.velocity.z - primary.velocity.z;
+        const along = tx * ux + ty * uy + tz * uz;
+        tx -= along * ux;
+        ty -= along * uy;
+        tz -= along * uz;
+        if (Math.hypot(tx, ty, tz) < 1e-12) {
+            [tx, ty, tz] = Math.abs(uy) < 0.9 ? [-uz, 0, ux] : [0, uz, -uy];
+        }
+        const motion = Math.hypot(tx, ty, tz);
+        tx /= motion;
+        ty /= motion;
+        tz /= motion;
+
+        let numerator = 0;
+        let denominator = 0;
+        let cc = 0, cs = 0, ss = 0, hc = 0, hs = 0;
+        for (let k = 0; k < count * 3; k += 3) {
+            const dx = positions[k] - primary.position.x;
+            const dy = positions[k + 1] - primary.position.y;
+            const dz = positions[k + 2] - primary.position.z;
+            const r = Math.hypot(dx, dy, dz);
+            if (r < 1e-9) continue;
+            const height = r - meanRadius;
+            const x = (dx * ux + dy * uy + dz * uz) / r;
+            const shape = legendreP2(x);
+            numerator += height * shape;
+            denominator += shape * shape;
+
+            const y = (dx * tx + dy * ty + dz * tz) / r;
+            const c = x * x - y * y;
+            const s = 2 * x * y;
+            cc += c * c;
+            cs += c * s;
+            ss += s * s;
+            hc += height * c;
+            hs += height * s;
+        }
+        bulgeAmplitude = denominator > 0 ? numerator / denominator : 0;
+
+        const determinant = cc * ss - cs * cs;
+        if (determinant > 1e-12) {
+            const a = (hc * ss - hs * cs) / determinant;
+            const b = (hs * cc - hc * cs) / determinant;
+            bulgeLagAngle = 0.5 * Math.atan2(b, a);
+        }
+    }
+
+    return {
+        time: core.simulationTime,
+        particleCount: count,
+        kineticEnergy,
+        potentialEnergy,
+        totalEnergy: kineticEnergy + potentialEnergy,
+        meanSeaLevel: meanRadius - primary.radius,
+        meanRadius,
+        maxRadius,
+        bulgeAmplitude,
+        bulgeLagAngle,
+        escapedCount,
+        clampedSubsteps: core.clampedSubsteps
+    };
+}
+
+export const DIAGNOSTIC_COLUMNS = [
+    'time', 'particleCount', 'kineticEnergy', 'potentialEnergy',
+    'totalEnergy', 'meanSeaLevel', 'meanRadius', 'maxRadius',
+    'bulgeAmplitude', 'bulgeLagAngle', 'escapedCount', 'clampedSubsteps'
+];
+
+export function toCsv(rows, columns) {
+    const lines = [columns.join(',')];
+    for (const row of rows) {
+        lines.push(columns.map(column => row[column] ?? '').join(','));
+    }
+    return lines.join('\n') + '\n';
+}
+
+// Reduces a run's diagnostics rows to one row of summary metrics
+export function summarizeDiagnostics(rows) {
+    const first = rows[0];
+    const last = rows[rows.length - 1];
+    const bulges = rows.map(row => row.bulgeAmplitude);
+    const energyScale = Math.abs(first.totalEnergy) || 1;
+
+    return {
+        duration: last.time - first.time,
+        finalParticleCount: last.particleCount,
+        finalMeanSeaLevel: last.meanSeaLevel,
+        seaLevelChange: last.meanSeaLevel - first.meanSeaLevel,
+        meanBulgeAmplitude: bulges.reduce((sum, value) => sum + value, 0) / bulges.length,
+        maxBulgeAmplitude: Math.max(...bulges),
+        finalKineticEnergy: last.kineticEnergy,
+        relativeEnergyDrift: (last.totalEnergy - first.totalEnergy) / energyScale,
+        clampedSubsteps: last.clampedSubsteps - first.clampedSubsteps
+    };
+}
+
+export const SUMMARY_COLUMNS = [
+    'duration', 'finalParticleCount', 'finalMeanSeaLevel', 'seaLevelChange',
+    'meanBulgeAmplitude', 'maxBulgeAmplitude', 'finalKineticEnergy', 'relativeEnergyDrift',
+    'clampedSubsteps'
+];
+
+// Positions and velocities of every body and particle, as plain arrays so
+// the result can be written straight out as JSON
+export function takeSnapshot(core) {
+    return {
+        time: core.simulationTime,
+        bodies: core.bodies.map(body => ({
+            id: body.id,
+            position: [body.position.x, body.position.y, body.position.z],
+            velocity: [body.velocity.x, body.velocity.y, body.velocity.z],
+            rotationAngle: body.rotationAngle
+        })),
+        particles: {
+            positions: Array.from(core.particles.positions),
+            velocities: Array.from(core.particles.velocities)
+        }
+    };
+}
@@ -0,0 +1,257 @@
+import { measureDiagnostics, toCsv, DIAGNOSTIC_COLUMNS } from './Diagnostics.js';
+
+// Live time series of the simulation's diagnostics, drawn on small canvases
+// in the control panel. A timer measures the view's core a few times a
+// second of wall time while simulated time advances; pausing stops the
+// timer. The history can be zoomed to a window of recent simulated time
+// and exported as CSV.
+
+const CHARTS = [
+    {
+        title: 'Energy',
+        series: [
+            { key: 'kineticEnergy', label: 'Kinetic', color: '#d62728' },
+            { key: 'potentialEnergy', label: 'Potential', color: '#1f77b4' },
+            { key: 'totalEnergy', label: 'Total', color: '#333333' }
+        ]
+    },
+    {
+        title: 'Fluid radius',
+        series: [
+            { key: 'meanRadius', label: 'Mean', color: '#1f77b4' },
+            { key: 'maxRadius', label: 'Max', color: '#ff7f0e' }
+        ]
+    },
+    { title: 'Bulge amplitude', series: [{ key: 'bulgeAmplitude', label: 'Amplitude', color: '#2ca02c' }] },
+    { title: 'Bulge lag (deg)', series: [{ key: 'bulgeLagAngle', label: 'Lag', color: '#9467bd', scale: 180 / Math.PI }] },
+    { title: 'Escaped particles', series: [{ key: 'escapedCount', label: 'Escaped', color: '#8c564b' }] },
+    { title: 'Step time (ms)', series: [{ key: 'stepTime', label: 'Step', color: '#7f7f7f' }] }
+];
+
+const COLUMNS = [...DIAGNOSTIC_COLUMNS, 'stepTime'];
+
+const SAMPLE_INTERVAL = 250;   // Wall-clock milliseconds between samples
+const MAX_SAMPLES = 10000;     // Oldest samples are dropped beyond this
+const ZOOM_FACTOR = 2;
+
+const WIDTH = 260;
+const HEIGHT = 90;
+const MARGIN = { top: 14, right: 4, bottom: 12, left: 4 };
+
+// Hands a CSV string to the browser as a file download
+export function downloadCsv(name, csv) {
+    const link = document.createElement('a');
+    link.href = URL.createObjectURL(new Blob([csv], { type: 'text/csv' }));
+    link.download = name;
+    link.click();
+    URL.revokeObjectURL(link.href);
+}
+
+export class DiagnosticsCharts {
+    constructor(container, simulator) {
+        this.simulator = simulator;
+        this.rows = [];
+        this.paused = false;
+        this.span = 0;                 // Simulated seconds shown, 0 for the whole history
+        this.timer = null;
+
+        const buttons = document.createElement('div');
+        buttons.className = 'control-item';
+        this.pauseButton = this.createButton(buttons, 'Pause', 'Stop recording to inspect the charts', () => this.togglePause());
+        this.createButton(buttons, '+', 'Zoom in on recent time', () => this.zoom(1 / ZOOM_FACTOR));
+        this.createButton(buttons, '−', 'Zoom out', () => this.zoom(ZOOM_FACTOR));
+        this.createButton(buttons, 'CSV', 'Download the recorded diagnostics', () => this.exportCsv());
+        container.appendChild(buttons);
+
+        this.spanLabel = document.createElement('div');
+        this.spanLabel.className = 'range-info';
+        container.appendChild(this.spanLabel);
+
+        this.canvases = CHARTS.map(() => {
+            const canvas = document.createElement('canvas');
+            canvas.width = WIDTH;
+            canvas.height = HEIGHT;
+            canvas.className = 'chart-canvas';
+            canvas.title = 'Scroll to zoom, double-click to show everything';
+            canvas.addEventListener('wheel', (e) => {
+                e.preventDefault();
+                this.zoom(e.deltaY > 0 ? ZOOM_FACTOR : 1 / ZOOM_FACTOR);
+            });
+            canvas.addEventListener('dblclick', () => {
+                this.span = 0;
+                this.draw();
+            });
+            container.appendChild(canvas);
+            return canvas;
+        });
+
+        this.draw();
+        this.start();
+    }
+
+    createButton(parent, text, tooltip, onClick) {
+        const button = document.createElement('button');
+        button.textContent = text;
+        button.title = tooltip;
+        button.addEventListener('click', onClick);
+        parent.appendChild(button);
+        return button;
+    }
+
+    start() {
+        if (this.timer === null) {
+            this.timer = setInterval(() => this.sample(), SAMPLE_INTERVAL);
+        }
+    }
+
+    stop() {
+        clearInterval(this.timer);
+        this.timer = null;
+    }
+
+    // Takes a sample if the simulation has moved on since the last one
+    sample() {
+        const core = this.simulator.core;
+        const last = this.rows[this.rows.length - 1];
+        if (last && core.simulationTime === last.time) return;
+
+        this.rows.push({ ...measureDiagnostics(core), stepTime: this.simulator.stepTime });
+        if (this.rows.length > MAX_SAMPLES) {
+            this.rows.splice(0, this.rows.length - MAX_SAMPLES);
+        }
+        this.draw();
+    }
+
+    // Drops the history, e.g. when the simulation clock restarts
+    clear() {
+        this.rows = [];
+        this.draw();
+    }
+
+    togglePause() {
+        this.paused = !this.paused;
+        this.pauseButton.textContent = this.paused ? 'Resume' : 'Pause';
+        if (this.paused) {
+            this.stop();
+        } else {
+            this.start();
+        }
+    }
+
+    zoom(factor) {
+        const history = this.historySpan();
+        const span = (this.span || history) * factor;
+        this.span = span >= history ? 0 : span;
+        this.draw();
+    }
+
+    historySpan() {
+        if (this.rows.length < 2) return 0;
+        return this.rows[this.rows.length - 1].time - this.rows[0].time;
+    }
+
+    exportCsv() {
+        downloadCsv('diagnostics.csv', toCsv(this.rows, COLUMNS));
+    }
+
+    // Samples inside the current time window
+    visibleRows() {
+        if (!this.span || this.rows.length === 0) return this.rows;
+        const start = this.rows[this.rows.length - 1].time - this.span;
+        const first = this.rows.findIndex(row => row.time >= start);
+        return this.rows.slice(Math.max(0, first - 1));
+    }
+
+    draw() {
+        const rows = this.visibleRows();
+        this.spanLabel.textContent = this.span
+            ? `Last ${this.span.toPrecision(3)} s of ${this.historySpan().toFixed(1)} s`
+            : `All ${this.historySpan().toFixed(1)} s`;
+        CHARTS.forEach((chart, index) => this.drawChart(this.canvases[index], chart, rows));
+    }
+
+    drawChart(canvas, chart, rows) {
+        const context = canvas.getContext('2d');
+        if (!context) return;
+        context.clearRect(0, 0, WIDTH, HEIGHT);
+        context.fillStyle = '#ffffff';
+        context.fillRect(0, 0, WIDTH, HEIGHT);
+        context.font = '10px Arial, sans-serif';
+        context.textBaseline = 'top';
+        context.fillStyle = '#333333';
+        context.fillText(chart.title, MARGIN.left, 2);
+
+        const value = (row, series) => row[series.key] * (series.scale ?? 1);
+        let min = Infinity;
+        let max = -Infinity;
+        for (const row of rows) {
+            for (const series of chart.series) {
+                const v = value(row, series);
+                if (!Number.isFinite(v)) continue;
+                min = Math.min(min, v);
+                max = Math.max(max, v);
+            }
+        }
+        if (rows.length < 2 || min > max) return;
+        if (max - min < 1e-12 * Math.max(1, Math.abs(max))) {
+            // Flat line: give it some room so it sits mid-plot
+            const pad = Math.abs(max) * 0.01 || 1;
+            min -= pad;
+            max += pad;
+        }
+
+        const startTime = rows[0].time;
+        const timeSpan = rows[rows.length - 1].time - startTime || 1;
+        const plotWidth = WIDTH - MARGIN.left - MARGIN.right;
+        const plotHeight = HEIGHT - MARGIN.top - MARGIN.bottom;
+        const toX = time => MARGIN.left + (time - startTime) / timeSpan * plotWidth;
+        const toY = v => MARGIN.top + (max - v) / (max - min) * plotHeight;
+
+        context.strokeStyle = '#dddddd';
+        context.strokeRect(MARGIN.left, MARGIN.top, plotWidth, plotHeight);
+
+        let legendX = WIDTH - MARGIN.right;
+        for (const series of [...chart.series].reverse()) {
+            context.strokeStyle = series.color;
+            context.beginPath();
+            let drawing = false;
+            for (const row of rows) {
+                const v = value(row, series);
+                if (!Number.isFinite(v)) {
+                    drawing = false;
+                    continue;
+                }
+                if (drawing) {
+                    context.lineTo(toX(row.time), toY(v));
+                } else {
+                    context.moveTo(toX(row.time), toY(v));
+                    drawing = true;
+                }
+            }
+            context.stroke();
+
+            if (chart.series.length > 1) {
+                legendX -= context.measureText(series.label).width;
+                context.fillStyle = series.color;
+                context.fillText(series.label, legendX, 2);
+                legendX -= 6;
+            }
+        }
+
+        // Value range on the left, time range along the bottom
+        context.fillStyle = '#666666';
+        context.fillText(formatValue(max), MARGIN.left + 2, MARGIN.top + 1);
+        context.textBaseline = 'bottom';
+        context.fillText(formatValue(min), MARGIN.left + 2, MARGIN.top + plotHeight - 1);
+        context.fillText(`${startTime.toFixed(1)} s`, MARGIN.left, HEIGHT);
+        const endLabel = `${(startTime + timeSpan).toFixed(1)} s`;
+        context.fillText(endLabel, WIDTH - MARGIN.right - context.measureText(endLabel).width, HEIGHT);
+    }
+}
+
+function formatValue(value) {
+    const magnitude = Math.abs(value);
+    return magnitude !== 0 && (magnitude < 1e-2 || magnitude >= 1e5)
+        ? value.toExponential(2)
+        : value.toFixed(magnitude < 10 ? 3 : 1);
+}
@@ -1,179 +1,280 @@
 import * as THREE from 'three';
-
+import { orbitPath } from './KeplerOrbit.js';
+import { SimulationCore } from './SimulationCore.js';
+
+// Body meshes are built with their poles along +Y
+const MESH_POLE = new THREE.Vector3(0, 1, 0);
+const spinRotation = new THREE.Quaternion();
+const DEGREES = Math.PI / 180;
+
+// Tide gauge samples kept for display and export, per gauge; a gauge
+// records every physics step, so this is several minutes of history
+const MAX_GAUGE_SAMPLES = 100000;
+
+// Three.js view of a SimulationCore. The core owns all physics state; this
+// class builds meshes, orbit lines and the particle cloud from it and copies
+// positions across after every update.
+//
+// When workers are available the stepping runs in SimulationWorker.js. The
+// local core is then a mirror: every state-changing call is applied to it
+// and forwarded to the worker, and completed frames from the worker are
+// copied into it for rendering.
 export class FluidSimulator {
     constructor() {
-        // Store default parameters
-        this.defaultParameters = {
-            // Planet parameters
-            planetRadius: 5.0,
-            planetMass: 1000.0,
-            planetRotationSpeed: 0.0,
-            planetRotationAngle: 0,
-            
-            // Moon parameters
-            moonRadius: 1.0,
-            moonMass: 100.0,
-            moonOrbitRadius: 15.0,
-            moonInitialAngle: 0,
-            moonOrbitalSpeed: 0.5,
-            moonRotationSpeed: 0.0,
-            moonRotationAngle: 0,
-            
-            // Fluid parameters
-            particleCount: 1000,
-            particleSize: 0.1,
-            fluidHeight: 0.5,    // Height of fluid above planet surface
-            fluidSpread: 0.8,    // How much of planet surface is covered (0-1)
-            
-            // Physics parameters
-            gravity: -9.81,
-            viscosity: 1.0,
-            density: 1000.0,
-            gravitationalConstant: 6.67430e-11,
-            timeScale: 1.0,
-            scale: 1.0,
-            
-            // Surface tension parameters
-            surfaceTensionStrength: 0.8,    // Strength of surface tension between particles
-            surfaceTensionRadius: 1.0,      // Radius within which particles affect each other
-            cohesionStrength: 0.5,          // Strength of particle cohesion
-            tensionResistance: 0.3          // Resistance to stretching/separation
-        };
+        this.bodyGroup = new THREE.Group();
+        this.particleSystem = null;
+        this.onParticleSystemUpdate = null;
+        this.probeMarkers = [];
+        this.stepTime = 0;            // Wall-clock milliseconds per physics step, last update that stepped
+
+        this.core = new SimulationCore();
+        this.core.tideGaugeLimit = MAX_GAUGE_SAMPLES;
+        this.core.onBodiesChange = () => this.rebuildBodyMeshes();
+        this.core.onBodyShapeChange = (body) => this.rebuildBodyShape(body);
+        this.core.onParticlesReset = () => this.rebuildParticleSystem();
+        this.core.onProbesChange = () => this.resetProbes();
+
+        // The core built its bodies and particles before the hooks existed
+        this.rebuildBodyMeshes();
+        this.rebuildParticleSystem();
+
+        this.worker = null;
+        this.generation = 0;          // Bumped by every forwarded call; older frames are stale
+        this.probeGeneration = 0;     // First generation recording the current tide gauges
+        this.frameInFlight = false;
+        this.pendingDelta = 0;        // Wall time not yet sent to the worker
+        this.returnedBuffers = [];    // Frame arrays to hand back for reuse
+        if (typeof Worker !== 'undefined') {
+            this.worker = new Worker(new URL('./SimulationWorker.js', import.meta.url), { type: 'module' });
+            this.worker.onmessage = ({ data }) => this.receiveFrame(data);
+        }
+    }
 
-        // Clone default parameters for current use
-        this.parameters = { ...this.defaultParameters };
+    get parameters() {
+        return this.core.parameters;
+    }
 
-        this.planet = this.createPlanet();
-        this.moon = this.createMoon();
-        this.orbitLine = this.createOrbitLine();
-        this.initializeParticles();
+    get defaultParameters() {
+        return this.core.defaultParameters;
+    }
 
-        this.frameCount = 0;
-        this._tempVec1 = new THREE.Vector3();
-        this._tempVec2 = new THREE.Vector3();
-        this._tempVec3 = new THREE.Vector3();
-        this._tempVec4 = new THREE.Vector3();
+    get simulationTime() {
+        return this.core.simulationTime;
+    }
 
-        // Add rotation axis
-        this.rotationAxis = new THREE.Vector3(0, 1, 0);
+    get effectiveTimeStep() {
+        return this.core.effectiveTimeStep;
     }
 
-    createPlanet() {
-        const geometry = new THREE.SphereGeometry(this.parameters.planetRadius, 32, 32);
-        const material = new THREE.MeshPhongMaterial({ 
-            color: 0x44aa44,
-            // Add some surface texture or pattern to make rotation visible
-            bumpMap: this.createRotationTexture(),
-            bumpScale: 0.1
-        });
-        return new THREE.Mesh(geometry, material);
+    get clampedSubsteps() {
+        return this.core.clampedSubsteps;
     }
 
-    createMoon() {
-        const geometry = new THREE.SphereGeometry(this.parameters.moonRadius, 32, 32);
-        const material = new THREE.MeshPhongMaterial({ 
-            color: 0x800080,
-            // Add some surface texture or pattern to make rotation visible
-            bumpMap: this.createRotationTexture(),
-            bumpScale: 0.1
-        });
-        const moon = new THREE.Mesh(geometry, material);
-        
-        const angle = this.parameters.moonInitialAngle;
-        const x = Math.cos(angle) * this.parameters.moonOrbitRadius;
-        const z = Math.sin(angle) * this.parameters.moonOrbitRadius;
-        moon.position.set(x, 0, z);
-        
-        return moon;
-    }
-
-    createOrbitLine() {
-        const segments = 64;
-        const geometry = new THREE.BufferGeometry();
-        const material = new THREE.LineBasicMaterial({ color: 0x888888, opacity: 0.5, transparent: true });
-        
-        // Create orbit preview points
-        const points = [];
-        for (let i = 0; i <= segments; i++) {
-            const angle = (i / segments) * Math.PI * 2;
-            const x = Math.cos(angle) * this.parameters.moonOrbitRadius;
-            const z = Math.sin(angle) * this.parameters.moonOrbitRadius;
-            points.push(new THREE.Vector3(x, 0, z));
+    // Steps the local core, or asks the worker for the next frame. The
+    // worker gets one request at a time; wall time that passes while it is
+    // busy is added to the next request.
+    update(frameDeltaSeconds) {
+        if (!this.worker) {
+            const started = performance.now();
+            const steps = this.core.update(frameDeltaSeconds);
+            if (steps > 0) {
+                this.stepTime = (performance.now() - started) / steps;
+            }
+            this.syncBodyMeshes();
+            this.syncParticles();
+            return steps;
         }
-        
-        geometry.setFromPoints(points);
-        return new THREE.Line(geometry, material);
+
+        this.pendingDelta += frameDeltaSeconds;
+        if (!this.frameInFlight) {
+            const buffers = this.returnedBuffers;
+            this.returnedBuffers = [];
+            this.worker.postMessage({ type: 'advance', frameDeltaSeconds: this.pendingDelta, buffers }, buffers);
+            this.pendingDelta = 0;
+            this.frameInFlight = true;
+        }
+        return 0;
     }
 
-    updateMoonPosition(angle) {
-        const x = Math.cos(angle) * this.parameters.moonOrbitRadius;
-        const z = Math.sin(angle) * this.parameters.moonOrbitRadius;
-        this.moon.position.set(x, 0, z);
+    receiveFrame(frame) {
+        this.frameInFlight = false;
+        this.returnedBuffers.push(frame.positions.buffer, frame.velocities.buffer);
+
+        // Gauge samples stay valid across unrelated calls, so keep the
+        // series unbroken unless the gauges themselves were replaced
+        if (frame.generation >= this.probeGeneration) {
+            frame.probes.forEach((samples, index) => this.core.tideGauges[index].append(samples));
+        }
+
+        // Frames computed before the latest forwarded call don't match the
+        // mirror any more (e.g. the particle count changed)
+        if (frame.generation !== this.generation) return;
+
+        const particles = this.core.particles;
+        if (frame.positions.length === particles.count * 3) {
+            particles.positions.set(frame.positions);
+            particles.velocities.set(frame.velocities);
+        }
+        this.core.importBodyState(frame.bodies);
+        this.core.simulationTime = frame.simulationTime;
+        this.core.effectiveTimeStep = frame.effectiveTimeStep;
+        this.core.clampedSubsteps = frame.clampedSubsteps;
+        if (frame.stepTime !== null) {
+            this.stepTime = frame.stepTime;
+        }
+
+        this.syncBodyMeshes();
+        this.syncParticles();
     }
 
-    createRotationTexture() {
-        // Create a simple texture to make rotation visible
-        const canvas = document.createElement('canvas');
-        canvas.width = 64;
-        canvas.height = 64;
-        const ctx = canvas.getContext('2d');
-        
-        // Draw some patterns
-        ctx.fillStyle = '#ffffff';
-        ctx.fillRect(0, 0, 64, 64);
-        ctx.fillStyle = '#cccccc';
-        ctx.fillRect(0, 0, 32, 32);
-        ctx.fillRect(32, 32, 32, 32);
-        
-        const texture = new THREE.CanvasTexture(canvas);
-        texture.wrapS = THREE.RepeatWrapping;
-        texture.wrapT = THREE.RepeatWrapping;
-        return texture;
+    // Applies a state-changing call to the local core and the worker
+    forward(method, ...args) {
+        const result = this.core[method](...args);
+        if (this.worker) {
+            this.generation++;
+            this.worker.postMessage({ type: 'call', method, args, generation: this.generation });
+        }
+        return result;
+    }
+
+    setParameter(name, value) {
+        this.forward('setParameter', name, value);
+        // Rotation resets and orbit edits move bodies outside of update()
+        this.syncBodyMeshes();
+        this.syncParticles();
+    }
+
+    setBodies(definitions) {
+        this.forward('setBodies', definitions);
+    }
+
+    setForces(definitions) {
+        this.forward('setForces', definitions);
+    }
+
+    addForce(definition) {
+        return this.forward('addForce', definition);
+    }
+
+    removeForce(index) {
+        this.forward('removeForce', index);
+    }
+
+    setForceValue(index, name, value) {
+        this.forward('setForceValue', index, name, value);
+    }
+
+    getForces() {
+        return this.core.getForces();
+    }
+
+    setSeed(seed) {
+        this.forward('setSeed', seed);
     }
 
     initializeParticles() {
-        this.particles = [];
-        const geometry = new THREE.BufferGeometry();
-        const positions = new Float32Array(this.parameters.particleCount * 3);
-        const colors = new Float32Array(this.parameters.particleCount * 3);
-
-        // Calculate the area of planet surface to cover based on fluidSpread
-        const maxPhi = Math.PI * this.parameters.fluidSpread;
-        const phiStart = (Math.PI - maxPhi) / 2; // Center the fluid coverage
-
-        for (let i = 0; i < this.parameters.particleCount; i++) {
-            // Generate evenly distributed spherical coordinates
-            const theta = Math.random() * 2 * Math.PI;  // Longitude (0 to 2π)
-            const phi = phiStart + (Math.random() * maxPhi);  // Latitude (controlled by fluidSpread)
-            
-            // Calculate position exactly at planet surface + fluidHeight
-            const radius = this.parameters.planetRadius + this.parameters.fluidHeight;
-            const x = radius * Math.sin(phi) * Math.cos(theta);
-            const y = radius * Math.sin(phi) * Math.sin(theta);
-            const z = radius * Math.cos(phi);
-
-            // Create particle with zero initial velocity
-            const particle = {
-                position: new THREE.Vector3(x, y, z),
-                velocity: new THREE.Vector3(0, 0, 0),
-                density: 0,
-                pressure: 0
-            };
-            this.particles.push(particle);
-
-            // Set positions and colors
-            positions[i * 3] = x;
-            positions[i * 3 + 1] = y;
-            positions[i * 3 + 2] = z;
-
-            // Blue color for water particles
-            colors[i * 3] = 0.0;     // R
-            colors[i * 3 + 1] = 0.5; // G
-            colors[i * 3 + 2] = 1.0; // B
+        this.forward('initializeParticles');
+    }
+
+    applyScenario(scenario, fallbackSeed) {
+        this.forward('applyScenario', scenario, fallbackSeed);
+        this.syncBodyMeshes();
+    }
+
+    resetToDefault() {
+        this.forward('resetToDefault');
+    }
+
+    setProbes(definitions) {
+        this.forward('setProbes', definitions);
+    }
+
+    getProbes() {
+        return this.core.getProbes();
+    }
+
+    get tideGauges() {
+        return this.core.tideGauges;
+    }
+
+    resetProbes() {
+        // The call replacing the gauges is forwarded as the next generation
+        this.probeGeneration = this.generation + 1;
+        this.rebuildProbeMarkers();
+    }
+
+    // Small spheres on the fluid host's mesh, one per tide gauge, so they
+    // turn with the body
+    rebuildProbeMarkers() {
+        for (const marker of this.probeMarkers) {
+            marker.removeFromParent();
+            marker.geometry.dispose();
+            marker.material.dispose();
+        }
+        this.probeMarkers = [];
+
+        const body = this.core.primaryBody;
+        if (!body || !body.mesh) return;
+        for (const gauge of this.core.tideGauges) {
+            const latitude = gauge.latitude * DEGREES;
+            const longitude = gauge.longitude * DEGREES;
+            const marker = new THREE.Mesh(
+                new THREE.SphereGeometry(0.04 * body.radius, 8, 8),
+                new THREE.MeshBasicMaterial({ color: 0xff4444 }));
+            marker.position.set(
+                Math.cos(latitude) * Math.cos(longitude),
+                Math.sin(latitude),
+                -Math.cos(latitude) * Math.sin(longitude)).multiplyScalar(body.radius);
+            marker.name = gauge.name;
+            body.mesh.add(marker);
+            this.probeMarkers.push(marker);
+        }
+    }
+
+    rebuildBodyMeshes() {
+        for (const object of [...this.bodyGroup.children]) {
+            this.bodyGroup.remove(object);
+            object.geometry.dispose();
+            object.material.dispose();
+        }
+
+        for (const body of this.core.bodies) {
+            body.mesh = this.createBodyMesh(body);
+            this.bodyGroup.add(body.mesh);
+            if (body.parent) {
+                body.orbitLine = this.createOrbitLine(body);
+                this.bodyGroup.add(body.orbitLine);
+            }
         }
+        this.rebuildProbeMarkers();
+        this.syncBodyMeshes();
+    }
+
+    rebuildBodyShape(body) {
+        if (!body.mesh) return;
 
-        geometry.setAttribute('position', new THREE.BufferAttribute(positions, 3));
-        geometry.setAttribute('color', new THREE.BufferAttribute(colors, 3));
+        body.mesh.geometry.dispose();
+        body.mesh.geometry = new THREE.SphereGeometry(body.radius, 32, 32);
+        if (body === this.core.primaryBody) {
+            this.rebuildProbeMarkers();
+        }
+        if (body.orbitLine) {
+            body.orbitLine.geometry.dispose();
+            body.orbitLine.geometry = this.createOrbitLine(body).geometry;
+        }
+    }
+
+    rebuildParticleSystem() {
+        if (this.particleSystem) {
+            this.particleSystem.geometry.dispose();
+            this.particleSystem.material.dispose();
+        }
+
+        // The core's particle arrays are the vertex buffers themselves
+        const particles = this.core.particles;
+        const geometry = new THREE.BufferGeometry();
+        geometry.setAttribute('position', new THREE.BufferAttribute(particles.positions, 3));
+        geometry.setAttribute('color', new THREE.BufferAttribute(particles.colors, 3));
 
         const material = new THREE.PointsMaterial({
             size: this.parameters.particleSize,
@@ -184,222 +285,77 @@ export class FluidSimulator {
         });
 
         this.particleSystem = new THREE.Points(geometry, material);
-    }
-
-    update() {
-        const deltaTime = this.parameters.timeScale * 0.016;
 
-        // Update rotations
-        this.parameters.planetRotationAngle += this.parameters.planetRotationSpeed * deltaTime;
-        this.parameters.moonRotationAngle += this.parameters.moonRotationSpeed * deltaTime;
-
-        // Apply rotations
-        this.planet.rotation.y = this.parameters.planetRotationAngle;
-        this.moon.rotation.y = this.parameters.moonRotationAngle;
-
-        // Update moon orbital position
-        if (this.frameCount % 3 === 0) {
-            const currentAngle = Math.atan2(this.moon.position.z, this.moon.position.x);
-            const newAngle = currentAngle + this.parameters.moonOrbitalSpeed * deltaTime * 3;
-            this.updateMoonPosition(newAngle);
+        // Make sure the scene gets updated with the new particle system
+        if (this.onParticleSystemUpdate) {
+            this.onParticleSystemUpdate(this.particleSystem);
         }
+    }
 
-        // Optimize neighborhood calculation using spatial partitioning
-        const positions = this.particleSystem.geometry.attributes.position.array;
-        const gridSize = this.parameters.surfaceTensionRadius;
-        const spatialGrid = new Map();
-        
-        // First pass: Build spatial grid
-        for (let i = 0; i < this.particles.length; i++) {
-            const particle = this.particles[i];
-            const gridX = Math.floor(particle.position.x / gridSize);
-            const gridY = Math.floor(particle.position.y / gridSize);
-            const gridZ = Math.floor(particle.position.z / gridSize);
-            const gridKey = `${gridX},${gridY},${gridZ}`;
-            
-            if (!spatialGrid.has(gridKey)) {
-                spatialGrid.set(gridKey, []);
-            }
-            spatialGrid.get(gridKey).push(i);
-        }
+    createBodyMesh(body) {
+        const geometry = new THREE.SphereGeometry(body.radius, 32, 32);
+        const material = body.type === 'sun'
+            ? new THREE.MeshBasicMaterial({ color: body.color })
+            : new THREE.MeshPhongMaterial({
+                color: body.color,
+                // Add some surface texture or pattern to make rotation visible
+                bumpMap: this.createRotationTexture(),
+                bumpScale: 0.1
+            });
+        return new THREE.Mesh(geometry, material);
+    }
 
-        // Second pass: Update particles
-        const scaledDeltaTime = deltaTime * 0.08;
-        
-        for (let i = 0; i < this.particles.length; i++) {
-            const particle = this.particles[i];
-            
-            // Get nearby grid cells
-            const gridX = Math.floor(particle.position.x / gridSize);
-            const gridY = Math.floor(particle.position.y / gridSize);
-            const gridZ = Math.floor(particle.position.z / gridSize);
-            
-            const neighbors = [];
-            
-            // Check only neighboring cells
-            for (let dx = -1; dx <= 1; dx++) {
-                for (let dy = -1; dy <= 1; dy++) {
-                    for (let dz = -1; dz <= 1; dz++) {
-                        const key = `${gridX + dx},${gridY + dy},${gridZ + dz}`;
-                        const cellParticles = spatialGrid.get(key) || [];
-                        
-                        for (const j of cellParticles) {
-                            if (i === j) continue;
-                            
-                            const neighbor = this.particles[j];
-                            const distance = particle.position.distanceTo(neighbor.position);
-                            
-                            if (distance < this.parameters.surfaceTensionRadius) {
-                                neighbors.push({ particle: neighbor, distance });
-                            }
-                        }
-                    }
-                }
-            }
+    createOrbitLine(body) {
+        const geometry = new THREE.BufferGeometry();
+        const material = new THREE.LineBasicMaterial({ color: 0x888888, opacity: 0.5, transparent: true });
 
-            // Calculate forces (reuse vectors to reduce garbage collection)
-            const toPlanet = this._tempVec1 || (this._tempVec1 = new THREE.Vector3());
-            const toMoon = this._tempVec2 || (this._tempVec2 = new THREE.Vector3());
-            
-            toPlanet.copy(this.planet.position).sub(particle.position);
-            toMoon.copy(this.moon.position).sub(particle.position);
-            
-            const distanceToPlanet = toPlanet.length();
-            const distanceToMoon = toMoon.length();
-            
-            // Calculate gravitational forces
-            const totalForce = this.calculateGravitationalForce(particle.position, this.parameters.planetMass, this.planet.position, 1.0);
-            totalForce.add(this.calculateGravitationalForce(particle.position, this.parameters.moonMass, this.moon.position, 5.0));
-            
-            // Add surface tension and cohesion only if we have neighbors
-            if (neighbors.length > 0) {
-                const centerOfMass = this._tempVec3 || (this._tempVec3 = new THREE.Vector3());
-                centerOfMass.set(0, 0, 0);
-                
-                for (const {particle: neighbor, distance} of neighbors) {
-                    centerOfMass.add(neighbor.position);
-                    
-                    // Simplified tension forces
-                    const direction = this._tempVec4 || (this._tempVec4 = new THREE.Vector3());
-                    direction.copy(neighbor.position).sub(particle.position).normalize();
-                    direction.multiplyScalar((1 - distance / this.parameters.surfaceTensionRadius) * 
-                                          this.parameters.surfaceTensionStrength);
-                    totalForce.add(direction);
-                }
-                
-                // Add cohesion force
-                centerOfMass.divideScalar(neighbors.length)
-                           .sub(particle.position)
-                           .multiplyScalar(this.parameters.cohesionStrength);
-                totalForce.add(centerOfMass);
-            }
-            
-            // Update physics
-            particle.velocity.add(totalForce.multiplyScalar(scaledDeltaTime));
-            particle.velocity.multiplyScalar(0.995); // Damping
-            particle.position.add(particle.velocity.multiplyScalar(scaledDeltaTime));
-            
-            // Simple collision response
-            if (distanceToMoon < this.parameters.moonRadius || 
-                distanceToPlanet < this.parameters.planetRadius + this.parameters.fluidHeight) {
-                particle.velocity.multiplyScalar(-0.5);
-            }
-            
-            // Update particle position in geometry
-            positions[i * 3] = particle.position.x;
-            positions[i * 3 + 1] = particle.position.y;
-            positions[i * 3 + 2] = particle.position.z;
-        }
+        // Create orbit preview points along the true ellipse
+        const points = orbitPath(body.getOrbitElements())
+            .map(({ x, y, z }) => new THREE.Vector3(x, y, z));
 
-        this.particleSystem.geometry.attributes.position.needsUpdate = true;
-        this.frameCount = (this.frameCount || 0) + 1;
+        geometry.setFromPoints(points);
+        return new THREE.Line(geometry, material);
     }
 
-    calculateGravitationalForce(particlePos, bodyMass, bodyPos, multiplier = 1.0) {
-        const direction = bodyPos.clone().sub(particlePos);
-        const distance = direction.length();
-        
-        // Adjusted minimum distance
-        const minDistance = 0.5;
-        const safeDist = Math.max(distance, minDistance);
-        
-        // Adjusted gravitational force scaling
-        const scaledG = this.parameters.gravitationalConstant * 1e10;
-        const forceMagnitude = (scaledG * bodyMass * multiplier) / (safeDist * safeDist);
-        
-        return direction.normalize().multiplyScalar(forceMagnitude);
+    createRotationTexture() {
+        // Create a simple texture to make rotation visible
+        const canvas = document.createElement('canvas');
+        canvas.width = 64;
+        canvas.height = 64;
+        const ctx = canvas.getContext('2d');
+
+        // Draw some patterns
+        ctx.fillStyle = '#ffffff';
+        ctx.fillRect(0, 0, 64, 64);
+        ctx.fillStyle = '#cccccc';
+        ctx.fillRect(0, 0, 32, 32);
+        ctx.fillRect(32, 32, 32, 32);
+
+        const texture = new THREE.CanvasTexture(canvas);
+        texture.wrapS = THREE.RepeatWrapping;
+        texture.wrapT = THREE.RepeatWrapping;
+        return texture;
     }
 
-    setParameter(name, value) {
-        if (name in this.parameters) {
-            this.parameters[name] = value;
-            
-            // Update relevant components based on parameter changes
-            if (['moonOrbitRadius', 'moonRadius'].includes(name)) {
-                this.moon.geometry = new THREE.SphereGeometry(this.parameters.moonRadius, 32, 32);
-                this.updateMoonPosition(Math.atan2(this.moon.position.z, this.moon.position.x));
-                this.orbitLine.geometry.dispose();
-                this.orbitLine.geometry = this.createOrbitLine().geometry;
-            }
-            else if (['planetRadius'].includes(name)) {
-                this.planet.geometry = new THREE.SphereGeometry(this.parameters.planetRadius, 32, 32);
-                
-                // Instead of full reinitialization, adjust particle positions relative to new radius
-                const radiusRatio = this.parameters.planetRadius / (this.parameters.planetRadius - value);
-                const positions = this.particleSystem.geometry.attributes.position.array;
-                
-                for (let i = 0; i < this.particles.length; i++) {
-                    const particle = this.particles[i];
-                    
-                    // Calculate new position maintaining relative height above surface
-                    const direction = particle.position.clone().normalize();
-                    const newRadius = this.parameters.planetRadius + this.parameters.fluidHeight;
-                    particle.position.copy(direction.multiplyScalar(newRadius));
-                    
-                    // Update geometry
-                    positions[i * 3] = particle.position.x;
-                    positions[i * 3 + 1] = particle.position.y;
-                    positions[i * 3 + 2] = particle.position.z;
-                    
-                    // Maintain current velocity direction but scale magnitude
-                    if (particle.velocity) {
-                        particle.velocity.multiplyScalar(0.5); // Dampen velocities during radius change
-                    }
-                }
-                
-                this.particleSystem.geometry.attributes.position.needsUpdate = true;
-            }
-            else if (name === 'particleCount') {
-                // Clean up old particle system
-                if (this.particleSystem) {
-                    this.particleSystem.geometry.dispose();
-                    this.particleSystem.material.dispose();
-                }
-                
-                // Reinitialize particles with new count
-                this.initializeParticles();
-                
-                // Make sure the scene gets updated with the new particle system
-                // We need to emit an event or notify the main app to update the scene
-                if (this.onParticleSystemUpdate) {
-                    this.onParticleSystemUpdate(this.particleSystem);
-                }
-            }
-            // Reset rotation angles when speeds are changed to zero
-            else if (name === 'planetRotationSpeed' && value === 0) {
-                this.parameters.planetRotationAngle = 0;
-            }
-            else if (name === 'moonRotationSpeed' && value === 0) {
-                this.parameters.moonRotationAngle = 0;
+    syncBodyMeshes() {
+        for (const body of this.core.bodies) {
+            body.mesh.position.copy(body.position);
+            // Tilt the mesh's +Y pole onto the spin axis, then spin about it
+            body.mesh.quaternion.setFromUnitVectors(MESH_POLE, body.spinAxis)
+                .premultiply(spinRotation.setFromAxisAngle(body.spinAxis, body.rotationAngle));
+            if (body.orbitLine) {
+                body.orbitLine.position.copy(body.parent.position);
             }
         }
     }
 
+    syncParticles() {
+        this.particleSystem.geometry.attributes.position.needsUpdate = true;
+    }
+
     getObjects() {
         return {
-            planet: this.planet,
-            moon: this.moon,
-            orbitLine: this.orbitLine,
+            bodies: this.bodyGroup,
             particles: this.particleSystem
         };
     }
@@ -407,45 +363,4 @@ export class FluidSimulator {
     getParticleSystem() {
         return this.particleSystem;
     }
-
-    // Helper method to generate random tangential vectors for particle distribution
-    getRandomTangentialVector(normal) {
-        // Create a random vector
-        const random = new THREE.Vector3(
-            Math.random() - 0.5,
-            Math.random() - 0.5,
-            Math.random() - 0.5
-        );
-        
-        // Make it perpendicular to the normal
-        const tangent = random.clone()
-            .sub(normal.multiplyScalar(random.dot(normal)))
-            .normalize();
-        
-        return tangent;
-    }
-
-    resetToDefault() {
-        // Reset moon position
-        this.updateMoonPosition(this.defaultParameters.moonInitialAngle);
-        
-        // Reset all parameters to defaults
-        Object.entries(this.defaultParameters).forEach(([key, value]) => {
-            this.setParameter(key, value);
-        });
-        
-        // Reset particle system to initial state
-        if (this.particleSystem) {
-            this.particleSystem.geometry.dispose();
-            this.particleSystem.material.dispose();
-        }
-        
-        // Reinitialize particles with default configuration
-        this.initializeParticles();
-        
-        // Notify main app about the new particle system
-        if (this.onParticleSystemUpdate) {
-            this.onParticleSystemUpdate(this.particleSystem);
-        }
-    }
-}
\ No newline at end of file
+}
@@ -0,0 +1,187 @@
+// Force fields evaluated per particle on top of gravity and the SPH forces.
+//
+// Each scenario `forces` entry is { type, enabled, ...fields }. Field
+// types describe their editable fields so the UI can build controls, and
+// add their acceleration into `out` from:
+//   apply(field, position, velocity, context, out)
+// where context = { bodies: Map(id -> body), primaryBody, surfaceRadius,
+// neighbors }, `neighbors` being the frame's SpatialHash for fields that
+// need to look at nearby particles.
+// Vectors are plain { x, y, z } so this module has no Three.js dependency.
+
+function readVector(value, out) {
+    out.x = value[0];
+    out.y = value[1];
+    out.z = value[2];
+    return out;
+}
+
+// A field's center is either a fixed point or the current position of the
+// body named by its `body` field
+function resolveCenter(field, context, out) {
+    const body = field.body ? context.bodies.get(field.body) : null;
+    if (body) {
+        out.x = body.position.x;
+        out.y = body.position.y;
+        out.z = body.position.z;
+        return out;
+    }
+    return readVector(field.center, out);
+}
+
+const scratch = { a: { x: 0, y: 0, z: 0 }, b: { x: 0, y: 0, z: 0 } };
+
+export const forceFieldTypes = {
+    // Constant acceleration everywhere
+    uniform: {
+        label: 'Uniform Field',
+        fields: [
+            { name: 'direction', type: 'vector', default: [0, -1, 0] },
+            { name: 'strength', type: 'number', default: 1.0 }
+        ],
+        apply(field, position, velocity, context, out) {
+            const d = readVector(field.direction, scratch.a);
+            const length = Math.hypot(d.x, d.y, d.z) || 1;
+            const scale = field.strength / length;
+            out.x += d.x * scale;
+            out.y += d.y * scale;
+            out.z += d.z * scale;
+        }
+    },
+
+    // Push away from (positive strength) or pull towards a center,
+    // falling off as 1/r^falloff
+    radial: {
+        label: 'Radial Field',
+        fields: [
+            { name: 'center', type: 'vector', default: [0, 0, 0] },
+            { name: 'body', type: 'string', default: '' },
+            { name: 'strength', type: 'number', default: 1.0 },
+            { name: 'falloff', type: 'number', default: 0 }
+        ],
+        apply(field, position, velocity, context, out) {
+            const c = resolveCenter(field, context, scratch.a);
+            const dx = position.x - c.x, dy = position.y - c.y, dz = position.z - c.z;
+            const r = Math.hypot(dx, dy, dz);
+            if (r < 1e-6) return;
+            const scale = field.strength / Math.pow(r, field.falloff) / r;
+            out.x += dx * scale;
+            out.y += dy * scale;
+            out.z += dz * scale;
+        }
+    },
+
+    // Swirl around an axis through the center, fading out beyond `radius`
+    vortex: {
+        label: 'Vortex',
+        fields: [
+            { name: 'center', type: 'vector', default: [0, 0, 0] },
+            { name: 'body', type: 'string', default: '' },
+            { name: 'axis', type: 'vector', default: [0, 1, 0] },
+            { name: 'strength', type: 'number', default: 1.0 },
+            { name: 'radius', type: 'number', default: 5.0 }
+        ],
+        apply(field, position, velocity, context, out) {
+            const c = resolveCenter(field, context, scratch.a);
+            const axis = readVector(field.axis, scratch.b);
+            const axisLength = Math.hypot(axis.x, axis.y, axis.z) || 1;
+            const ax = axis.x / axisLength, ay = axis.y / axisLength, az = axis.z / axisLength;
+
+            // Tangential direction is axis x offset
+            const dx = position.x - c.x, dy = position.y - c.y, dz = position.z - c.z;
+            const tx = ay * dz - az * dy;
+            const ty = az * dx - ax * dz;
+            const tz = ax * dy - ay * dx;
+            const distance = Math.hypot(tx, ty, tz);
+            if (distance < 1e-6) return;
+
+            const falloff = Math.exp(-(distance * distance) / (field.radius * field.radius));
+            const scale = field.strength * falloff / distance;
+            out.x += tx * scale;
+            out.y += ty * scale;
+            out.z += tz * scale;
+        }
+    },
+
+    // Wind blowing over the ocean: only particles within `depth` of the
+    // fluid surface feel it, and only its component along the surface
+    wind: {
+        label: 'Wind Stress',
+        fields: [
+            { name: 'direction', type: 'vector', default: [1, 0, 0] },
+            { name: 'strength', type: 'number', default: 1.0 },
+            { name: 'depth', type: 'number', default: 0.3 }
+        ],
+        apply(field, position, velocity, context, out) {
+            const body = context.primaryBody;
+            const nx0 = position.x - body.position.x;
+            const ny0 = position.y - body.position.y;
+            const nz0 = position.z - body.position.z;
+            const r = Math.hypot(nx0, ny0, nz0);
+            if (r < 1e-6 || r < context.surfaceRadius - field.depth) return;
+
+            const nx = nx0 / r, ny = ny0 / r, nz = nz0 / r;
+            const d = readVector(field.direction, scratch.a);
+            const normalPart = d.x * nx + d.y * ny + d.z * nz;
+            out.x += (d.x - normalPart * nx) * field.strength;
+            out.y += (d.y - normalPart * ny) * field.strength;
+            out.z += (d.z - normalPart * nz) * field.strength;
+        }
+    },
+
+    // Linear drag against motion relative to the planet
+    drag: {
+        label: 'Linear Drag',
+        fields: [
+            { name: 'coefficient', type: 'number', default: 0.1 }
+        ],
+        apply(field, position, velocity, context, out) {
+            const reference = context.primaryBody.velocity;
+            out.x -= field.coefficient * (velocity.x - reference.x);
+            out.y -= field.coefficient * (velocity.y - reference.y);
+            out.z -= field.coefficient * (velocity.z - reference.z);
+        }
+    },
+
+    // Softened inverse-square point attractor (negative strength repels)
+    attractor: {
+        label: 'Point Attractor',
+        fields: [
+            { name: 'center', type: 'vector', default: [0, 8, 0] },
+            { name: 'strength', type: 'number', default: 10.0 },
+            { name: 'softening', type: 'number', default: 0.5 }
+        ],
+        apply(field, position, velocity, context, out) {
+            const c = readVector(field.center, scratch.a);
+            const dx = c.x - position.x, dy = c.y - position.y, dz = c.z - position.z;
+            const r2 = dx * dx + dy * dy + dz * dz + field.softening * field.softening;
+            const scale = field.strength / (r2 * Math.sqrt(r2));
+            out.x += dx * scale;
+            out.y += dy * scale;
+            out.z += dz * scale;
+        }
+    }
+};
+
+// Fills in defaults for a scenario force entry
+export function createForceField(definition) {
+    const type = forceFieldTypes[definition.type];
+    if (!type) {
+        throw new Error(`Unknown force field type: ${definition.type}`);
+    }
+
+    const field = { type: definition.type, enabled: definition.enabled ?? true };
+    for (const { name, type: fieldType, default: fallback } of type.fields) {
+        const value = definition[name] ?? fallback;
+        field[name] = fieldType === 'vector' ? [...value] : value;
+    }
+    return field;
+}
+
+export function applyForceFields(fields, position, velocity, context, out) {
+    for (const field of fields) {
+        if (field.enabled) {
+            forceFieldTypes[field.type].apply(field, position, velocity, context, out);
+        }
+    }
+}
@@ -0,0 +1,198 @@
+// Splits the particle passes of SimulationCore.computeAccelerations across
+// a pool of ForceWorkers by spatial region.
+//
+// Space is cut along x into slabs of whole grid cells (cells are the
+// neighbor cutoff wide), balanced so every worker owns about the same
+// number of particles. Each worker hashes its slab plus a one-cell halo on
+// either side, which holds every neighbor of the particles it owns. An
+// evaluation runs in two phases separated by a barrier:
+//   1. density and pressure of the owned particles
+//   2. accelerations of the owned particles
+// The barrier is the halo exchange: phase 2 reads the densities of halo
+// particles, which their owners wrote in phase 1. Particle arrays live in
+// shared memory, so every worker writes its results in place and the
+// merge is simply the union of the owned sets. Neighbor lists come out in
+// the same order as a single-threaded search, so the results match it
+// bit for bit whatever the number of workers.
+//
+// Workers keep a mirror of the core for parameters, bodies and force
+// fields; forward(method, args) keeps it in sync like the page does with
+// SimulationWorker. Between the first evaluation of an update and
+// endSession() the workers sit in a loop driven through Atomics on a
+// shared control block instead of messages, so an evaluation costs two
+// wake-ups rather than two message round trips. Atomics.wait blocks, so
+// the pool can only be driven from a worker or from Node's main thread,
+// never from a page. NodeForcePool.js builds the pool on worker_threads
+// for the headless runners.
+// endSession() waits until every worker has acknowledged the exit, so no
+// worker can still be reading the control block when the next session
+// starts.
+
+// Slots of the shared control block
+const EPOCH = 0;   // Bumped to start each phase
+const PHASE = 1;   // What the workers do when woken
+const DONE = 2;    // Workers finished with the current phase
+
+export const PHASE_DENSITY = 1;
+export const PHASE_FORCES = 2;
+export const PHASE_EXIT = 3;
+
+// Shared scalars besides the body state
+export const SCALAR_PARTICLE_MASS = 0;
+export const SCALAR_CELL_SIZE = 1;
+export const SCALAR_COUNT = 2;
+export const SCALAR_FRAME = 3;          // 1 while the core is in its rotating frame
+export const SCALAR_FRAME_ANGLE = 4;
+export const SCALAR_FRAME_ACCELERATION = 5;   // x, y, z
+const SCALAR_SLOTS = 8;
+
+export class ForcePool {
+    constructor(size, createWorker = () => new Worker(new URL('./ForceWorker.js', import.meta.url), { type: 'module' })) {
+        this.size = size;
+        this.workers = Array.from({ length: size }, createWorker);
+
+        this.control = new Int32Array(new SharedArrayBuffer(3 * Int32Array.BYTES_PER_ELEMENT));
+        this.slabs = new Int32Array(new SharedArrayBuffer(2 * size * Int32Array.BYTES_PER_ELEMENT));
+        this.scalars = new Float64Array(new SharedArrayBuffer(SCALAR_SLOTS * Float64Array.BYTES_PER_ELEMENT));
+        this.bodyState = null;
+
+        this.inSession = false;
+        this.cellCounts = new Int32Array(0);
+    }
+
+    // Pool size for this machine: one worker per spare core, or 0 when
+    // shared memory isn't available or there's nothing to gain
+    static defaultSize(cores = (typeof navigator !== 'undefined' && navigator.hardwareConcurrency) || 1) {
+        if (typeof SharedArrayBuffer === 'undefined') return 0;
+        if (typeof self !== 'undefined' && self.crossOriginIsolated === false) return 0;
+        const size = Math.min(8, cores - 1);
+        return size >= 2 ? size : 0;
+    }
+
+    // Applies a state-changing call to every worker's mirror core
+    forward(method, args) {
+        for (const worker of this.workers) {
+            worker.postMessage({ type: 'call', method, args });
+        }
+    }
+
+    computeParticleAccelerations(core) {
+        const { positions, count } = core.particles;
+        const cutoff = Math.max(core.parameters.smoothingRadius, core.parameters.surfaceTensionRadius);
+
+        const bodyState = core.exportBodyState();
+        if (!this.inSession) {
+            this.beginSession(core, bodyState.length);
+        }
+        this.bodyState.set(bodyState);
+        this.scalars[SCALAR_PARTICLE_MASS] = core.particleMass;
+        this.scalars[SCALAR_CELL_SIZE] = cutoff;
+        this.scalars[SCALAR_COUNT] = count;
+        this.scalars[SCALAR_FRAME] = core.frame ? 1 : 0;
+        if (core.frame) {
+            this.scalars[SCALAR_FRAME_ANGLE] = core.frame.angle;
+            this.scalars[SCALAR_FRAME_ACCELERATION] = core.frame.acceleration.x;
+            this.scalars[SCALAR_FRAME_ACCELERATION + 1] = core.frame.acceleration.y;
+            this.scalars[SCALAR_FRAME_ACCELERATION + 2] = core.frame.acceleration.z;
+        }
+        this.partition(positions, count, cutoff);
+
+        this.runPhase(PHASE_DENSITY);
+        this.runPhase(PHASE_FORCES);
+    }
+
+    // Hands the shared arrays to the workers and puts them in their loop
+    beginSession(core, bodyStateLength) {
+        if (!this.bodyState || this.bodyState.length !== bodyStateLength) {
+            this.bodyState = new Float64Array(new SharedArrayBuffer(bodyStateLength * Float64Array.BYTES_PER_ELEMENT));
+        }
+
+        const epoch = Atomics.load(this.control, EPOCH);
+        this.workers.forEach((worker, index) => {
+            worker.postMessage({
+                type: 'session',
+                index,
+                epoch,
+                control: this.control,
+                slabs: this.slabs,
+                scalars: this.scalars,
+                bodyState: this.bodyState,
+                particles: core.particles.toArrays()
+            });
+        });
+        this.inSession = true;
+    }
+
+    // Releases the workers so they can take forwarded calls again. Call
+    // after every update that may have evaluated accelerations.
+    endSession() {
+        if (!this.inSession) return;
+        this.runPhase(PHASE_EXIT);
+        this.inSession = false;
+    }
+
+    signal(phase) {
+        Atomics.store(this.control, PHASE, phase);
+        Atomics.store(this.control, DONE, 0);
+        Atomics.add(this.control, EPOCH, 1);
+        Atomics.notify(this.control, EPOCH);
+    }
+
+    runPhase(phase) {
+        this.signal(phase);
+        let done;
+        while ((done = Atomics.load(this.control, DONE)) < this.size) {
+            Atomics.wait(this.control, DONE, done);
+        }
+    }
+
+    // Cuts the occupied x range into one slab of whole cells per worker,
+    // with about count / size particles each. The first and last slabs
+    // reach out to infinity so no particle is left unowned.
+    partition(positions, count, cellSize) {
+        const inverseCell = 1 / cellSize;
+        let minCell = Infinity;
+        let maxCell = -Infinity;
+        for (let i = 0; i < count; i++) {
+            const cell = Math.floor(positions[i * 3] * inverseCell);
+            if (cell < minCell) minCell = cell;
+            if (cell > maxCell) maxCell = cell;
+        }
+        if (count === 0) {
+            minCell = maxCell = 0;
+        }
+
+        const span = maxCell - minCell + 1;
+        if (this.cellCounts.length < span) {
+            this.cellCounts = new Int32Array(span);
+        } else {
+            this.cellCounts.fill(0, 0, span);
+        }
+        for (let i = 0; i < count; i++) {
+            this.cellCounts[Math.floor(positions[i * 3] * inverseCell) - minCell]++;
+        }
+
+        let cell = 0;
+        let owned = 0;
+        for (let w = 0; w < this.size; w++) {
+            this.slabs[w * 2] = w === 0 ? -0x80000000 : minCell + cell;
+            const target = Math.round(count * (w + 1) / this.size);
+            while (cell < span && owned + this.cellCounts[cell] <= target) {
+                owned += this.cellCounts[cell++];
+            }
+            // Always take at least one cell while any are left, so a slab
+            // holding more than its share doesn't stall the split
+            if (w < this.size - 1 && owned < target && cell < span) {
+                owned += this.cellCounts[cell++];
+            }
+            this.slabs[w * 2 + 1] = w === this.size - 1 ? 0x7fffffff : minCell + cell;
+        }
+    }
+
+    terminate() {
+        this.endSession();
+        for (const worker of this.workers) {
+            worker.terminate();
+        }
+    }
+}
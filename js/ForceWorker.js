@@ -0,0 +1,125 @@
+// One worker of a ForcePool, see ForcePool.js.
+//
+// Outside a session it applies forwarded { type: 'call', method, args }
+// messages to its mirror core. A { type: 'session' } message hands it the
+// shared particle arrays and control block; it then loops on the control
+// block until the pool signals PHASE_EXIT, running the phases of each
+// evaluation on its own slab. Every phase, the exit included, is
+// acknowledged through the DONE counter.
+//
+// In a browser this file is the worker script itself; under Node the
+// worker runs NodeForceWorker.js, which hands its messages to
+// handleMessage.
+import { SimulationCore } from './SimulationCore.js';
+import { SpatialHash } from './SpatialHash.js';
+import { ParticleStore } from './ParticleStore.js';
+import {
+    PHASE_DENSITY, PHASE_FORCES, PHASE_EXIT,
+    SCALAR_PARTICLE_MASS, SCALAR_CELL_SIZE, SCALAR_COUNT,
+    SCALAR_FRAME, SCALAR_FRAME_ANGLE, SCALAR_FRAME_ACCELERATION
+} from './ForcePool.js';
+
+const EPOCH = 0;
+const PHASE = 1;
+const DONE = 2;
+
+const core = new SimulationCore();
+const grid = new SpatialHash();
+let members = new Int32Array(0);
+let owned = new Int32Array(0);
+let memberCount = 0;
+let ownedCount = 0;
+let neighbors = null;
+
+// Collects the particles of this worker's slab and of the halo cells on
+// either side, in ascending index order as SpatialHash expects
+function selectParticles(slabs, index, cellSize) {
+    const { positions, count } = core.particles;
+    const lo = slabs[index * 2];
+    const hi = slabs[index * 2 + 1];
+    if (members.length < count) {
+        members = new Int32Array(count);
+        owned = new Int32Array(count);
+    }
+
+    const inverseCell = 1 / cellSize;
+    memberCount = 0;
+    ownedCount = 0;
+    for (let i = 0; i < count; i++) {
+        const cell = Math.floor(positions[i * 3] * inverseCell);
+        if (cell < lo - 1 || cell > hi) continue;
+        members[memberCount++] = i;
+        if (cell >= lo && cell < hi) {
+            owned[ownedCount++] = i;
+        }
+    }
+}
+
+// The coordinator's rotating frame, if it is in one
+function readFrame(scalars) {
+    if (!scalars[SCALAR_FRAME]) {
+        core.frame = null;
+        return;
+    }
+    core.frame = core.frameState();
+    core.frame.angle = scalars[SCALAR_FRAME_ANGLE];
+    core.frame.acceleration.set(scalars[SCALAR_FRAME_ACCELERATION],
+        scalars[SCALAR_FRAME_ACCELERATION + 1], scalars[SCALAR_FRAME_ACCELERATION + 2]);
+}
+
+function runPhase(phase, session) {
+    if (phase === PHASE_DENSITY) {
+        const cellSize = session.scalars[SCALAR_CELL_SIZE];
+        core.particles.count = session.scalars[SCALAR_COUNT];
+        core.particleMass = session.scalars[SCALAR_PARTICLE_MASS];
+        core.importBodyState(session.bodyState);
+        core.updateTidalReference();
+        readFrame(session.scalars);
+        if (core.parameters.selfGravity) {
+            // Every worker needs the whole tree; building it is cheap next
+            // to walking it for each owned particle
+            core.buildSelfGravityTree();
+        }
+
+        selectParticles(session.slabs, session.index, cellSize);
+        neighbors = grid.build(core.particles.positions, core.particles.count, cellSize, members, memberCount)
+            .buildNeighborLists(cellSize, owned, ownedCount);
+        core.computeDensityPressure(neighbors, owned, ownedCount);
+    } else if (phase === PHASE_FORCES) {
+        core.computeParticleAccelerations(neighbors, owned, ownedCount);
+    }
+}
+
+function runSession(session) {
+    const { control } = session;
+    core.particles = ParticleStore.fromArrays(session.particles);
+    let epoch = session.epoch;
+
+    for (;;) {
+        Atomics.wait(control, EPOCH, epoch);
+        epoch = Atomics.load(control, EPOCH);
+        const phase = Atomics.load(control, PHASE);
+        if (phase !== PHASE_EXIT) {
+            runPhase(phase, session);
+        }
+        Atomics.add(control, DONE, 1);
+        Atomics.notify(control, DONE);
+        if (phase === PHASE_EXIT) break;
+    }
+
+    // Forwarded calls must not touch the shared particles
+    core.particles = new ParticleStore();
+}
+
+export function handleMessage(data) {
+    if (data.type === 'call') {
+        core[data.method](...data.args);
+    } else if (data.type === 'session') {
+        runSession(data);
+    }
+}
+
+// Under Node, NodeForceWorker.js feeds handleMessage from worker_threads
+if (typeof self !== 'undefined') {
+    self.onmessage = ({ data }) => handleMessage(data);
+}
@@ -0,0 +1,164 @@
+// Time integration schemes for the simulator.
+//
+// An integrator advances a system by one step of size dt. The system
+// exposes two methods:
+//   getIntegrationState()        -> array of state blocks
+//                                   { count, positions, velocities, accelerations }
+//                                   with vectors packed [x0, y0, z0, x1, ...]
+//   computeAccelerations(offset) -> fills every block's accelerations for the
+//                                   current positions/velocities, evaluated
+//                                   `offset` seconds into the step
+// Blocks are plain typed arrays, so integrators don't depend on Three.js.
+
+function ensureScratch(store, key, length) {
+    if (!store[key] || store[key].length < length) {
+        store[key] = new Float64Array(length);
+    }
+    return store[key];
+}
+
+// Kick then drift with the updated velocity. First order, one force
+// evaluation per step, but symplectic so orbits don't spiral out.
+export const symplecticEuler = {
+    name: 'symplecticEuler',
+    label: 'Symplectic Euler',
+    step(system, dt) {
+        const blocks = system.getIntegrationState();
+        system.computeAccelerations(0);
+
+        for (const { count, positions, velocities, accelerations } of blocks) {
+            for (let k = 0; k < count * 3; k++) {
+                velocities[k] += accelerations[k] * dt;
+                positions[k] += velocities[k] * dt;
+            }
+        }
+    }
+};
+
+// Kick-drift-kick. Second order and time-reversible for position-only
+// forces; the closing kick uses the half-step velocity for velocity-dependent
+// forces such as viscosity and damping.
+export const velocityVerlet = {
+    name: 'velocityVerlet',
+    label: 'Velocity Verlet',
+    step(system, dt) {
+        const blocks = system.getIntegrationState();
+        const halfDt = dt * 0.5;
+        system.computeAccelerations(0);
+
+        for (const { count, positions, velocities, accelerations } of blocks) {
+            for (let k = 0; k < count * 3; k++) {
+                velocities[k] += accelerations[k] * halfDt;
+                positions[k] += velocities[k] * dt;
+            }
+        }
+
+        system.computeAccelerations(dt);
+
+        for (const { count, velocities, accelerations } of blocks) {
+            for (let k = 0; k < count * 3; k++) {
+                velocities[k] += accelerations[k] * halfDt;
+            }
+        }
+    }
+};
+
+// Drift-kick-drift. Second order like Velocity Verlet but with a single
+// force evaluation per step, taken at the midpoint.
+export const leapfrog = {
+    name: 'leapfrog',
+    label: 'Leapfrog',
+    step(system, dt) {
+        const blocks = system.getIntegrationState();
+        const halfDt = dt * 0.5;
+
+        for (const { count, positions, velocities } of blocks) {
+            for (let k = 0; k < count * 3; k++) {
+                positions[k] += velocities[k] * halfDt;
+            }
+        }
+
+        system.computeAccelerations(halfDt);
+
+        for (const { count, positions, velocities, accelerations } of blocks) {
+            for (let k = 0; k < count * 3; k++) {
+                velocities[k] += accelerations[k] * dt;
+                positions[k] += velocities[k] * halfDt;
+            }
+        }
+    }
+};
+
+// Classical fourth-order Runge-Kutta. Four force evaluations per step; not
+// symplectic, but very accurate over short spans.
+export const rk4 = {
+    name: 'rk4',
+    label: 'Runge-Kutta 4',
+    scratch: {},
+    step(system, dt) {
+        const blocks = system.getIntegrationState();
+        const length = blocks.reduce((sum, block) => sum + block.count * 3, 0);
+        const x0 = ensureScratch(this.scratch, 'x0', length);
+        const v0 = ensureScratch(this.scratch, 'v0', length);
+        const dx = ensureScratch(this.scratch, 'dx', length);
+        const dv = ensureScratch(this.scratch, 'dv', length);
+
+        // Save the initial state and clear the weighted sums
+        let base = 0;
+        for (const { count, positions, velocities } of blocks) {
+            for (let k = 0; k < count * 3; k++) {
+                x0[base + k] = positions[k];
+                v0[base + k] = velocities[k];
+            }
+            base += count * 3;
+        }
+        dx.fill(0, 0, length);
+        dv.fill(0, 0, length);
+
+        const stages = [
+            { offset: 0, weight: 1 / 6, next: 0.5 },
+            { offset: dt * 0.5, weight: 2 / 6, next: 0.5 },
+            { offset: dt * 0.5, weight: 2 / 6, next: 1.0 },
+            { offset: dt, weight: 1 / 6, next: 0 }
+        ];
+
+        for (const stage of stages) {
+            system.computeAccelerations(stage.offset);
+            const h = stage.next * dt;
+
+            base = 0;
+            for (const { count, positions, velocities, accelerations } of blocks) {
+                for (let k = 0; k < count * 3; k++) {
+                    // Accumulate this stage's slope (dx/dt = v, dv/dt = a)
+                    dx[base + k] += velocities[k] * stage.weight;
+                    dv[base + k] += accelerations[k] * stage.weight;
+
+                    // Move to the trial state for the next stage
+                    positions[k] = x0[base + k] + velocities[k] * h;
+                    velocities[k] = v0[base + k] + accelerations[k] * h;
+                }
+                base += count * 3;
+            }
+        }
+
+        base = 0;
+        for (const { count, positions, velocities } of blocks) {
+            for (let k = 0; k < count * 3; k++) {
+                positions[k] = x0[base + k] + dx[base + k] * dt;
+                velocities[k] = v0[base + k] + dv[base + k] * dt;
+            }
+            base += count * 3;
+        }
+    }
+};
+
+export const integrators = {
+    symplecticEuler,
+    velocityVerlet,
+    leapfrog,
+    rk4
+};
+
+export function getIntegrator(name) {
+    return integrators[name] || leapfrog;
+}
@@ -0,0 +1,75 @@
+// Two-body orbit helpers based on classical orbital elements.
+//
+// Elements are { semiMajorAxis, eccentricity, inclination,
+// argumentOfPeriapsis, ascendingNode } with angles in radians. The reference
+// plane is the scene's XZ plane with +Y as the reference pole, so an orbit
+// with zero inclination matches the old circular orbit in the XZ plane.
+
+// Solves Kepler's equation M = E - e sin E for the eccentric anomaly E
+// using Newton iteration.
+export function solveKepler(meanAnomaly, eccentricity) {
+    const M = ((meanAnomaly % (2 * Math.PI)) + 2 * Math.PI) % (2 * Math.PI);
+    let E = eccentricity < 0.8 ? M : Math.PI;
+
+    for (let i = 0; i < 50; i++) {
+        const f = E - eccentricity * Math.sin(E) - M;
+        const delta = f / (1 - eccentricity * Math.cos(E));
+        E -= delta;
+        if (Math.abs(delta) < 1e-12) break;
+    }
+
+    return E;
+}
+
+// Rotates a point given in the perifocal frame (periapsis along +p, motion
+// towards +q) into scene coordinates.
+function perifocalToScene(elements, p, q, out) {
+    const { inclination, argumentOfPeriapsis, ascendingNode } = elements;
+    const cosO = Math.cos(ascendingNode), sinO = Math.sin(ascendingNode);
+    const cosw = Math.cos(argumentOfPeriapsis), sinw = Math.sin(argumentOfPeriapsis);
+    const cosi = Math.cos(inclination), sini = Math.sin(inclination);
+
+    // Position within the orbital plane, measured from the ascending node
+    const u = cosw * p - sinw * q;
+    const v = sinw * p + cosw * q;
+
+    out.x = cosO * u - sinO * v * cosi;
+    out.z = sinO * u + cosO * v * cosi;
+    out.y = v * sini;
+    return out;
+}
+
+// Position relative to the focus for the given mean anomaly
+export function orbitalPosition(elements, meanAnomaly, out = { x: 0, y: 0, z: 0 }) {
+    const { semiMajorAxis: a, eccentricity: e } = elements;
+    const E = solveKepler(meanAnomaly, e);
+    const p = a * (Math.cos(E) - e);
+    const q = a * Math.sqrt(1 - e * e) * Math.sin(E);
+    return perifocalToScene(elements, p, q, out);
+}
+
+// Samples the full ellipse at evenly spaced eccentric anomalies
+export function orbitPath(elements, segments = 128) {
+    const { semiMajorAxis: a, eccentricity: e } = elements;
+    const points = [];
+
+    for (let i = 0; i <= segments; i++) {
+        const E = (i / segments) * Math.PI * 2;
+        const p = a * (Math.cos(E) - e);
+        const q = a * Math.sqrt(1 - e * e) * Math.sin(E);
+        points.push(perifocalToScene(elements, p, q, { x: 0, y: 0, z: 0 }));
+    }
+
+    return points;
+}
+
+// Velocity relative to the focus for the given mean anomaly, where
+// meanMotion is the orbit's angular rate in radians per unit time
+export function orbitalVelocity(elements, meanAnomaly, meanMotion, out = { x: 0, y: 0, z: 0 }) {
+    const { semiMajorAxis: a, eccentricity: e } = elements;
+    const E = solveKepler(meanAnomaly, e);
+    const EDot = meanMotion / (1 - e * Math.cos(E));
+    const p = -a * Math.sin(E) * EDot;
+    const q = a * Math.sqrt(1 - e * e) * Math.cos(E) * EDot;
+    return perifocalToScene(elements, p, q, out);
+}
@@ -0,0 +1,19 @@
+// ForcePool for the headless runners, with its workers on
+// node:worker_threads. Node's Worker has the postMessage/terminate pair
+// the pool drives, and the main thread may block in Atomics.wait, so the
+// CLI and sweep run the pool directly from the thread that owns the core.
+import { Worker } from 'node:worker_threads';
+import { availableParallelism } from 'node:os';
+import { ForcePool } from './ForcePool.js';
+
+// Size chosen for this machine, as ForcePool.defaultSize
+export function nodePoolSize() {
+    return ForcePool.defaultSize(availableParallelism());
+}
+
+// A pool of `size` workers, or null for single-threaded stepping. A
+// single worker would only add the hand-over cost.
+export function createNodeForcePool(size = nodePoolSize()) {
+    if (size < 2) return null;
+    return new ForcePool(size, () => new Worker(new URL('./NodeForceWorker.js', import.meta.url)));
+}
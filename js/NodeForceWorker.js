@@ -0,0 +1,5 @@
+// Entry point of a ForceWorker on node:worker_threads, see NodeForcePool.js
+import { parentPort } from 'node:worker_threads';
+import { handleMessage } from './ForceWorker.js';
+
+parentPort.on('message', handleMessage);
@@ -0,0 +1,90 @@
+// Fluid particle state as a struct of arrays.
+//
+// Vector quantities are packed [x0, y0, z0, x1, ...]. Positions and colors
+// are Float32Arrays so the renderer can hand them to the GPU as they are;
+// the view builds its BufferAttributes directly on these arrays.
+// Velocities and accelerations are Float64Arrays so long integrations
+// don't lose the small per-step velocity changes to rounding. All other
+// per-particle data lives here too so the physics loops stay allocation
+// free. With `shared` set the arrays are backed by SharedArrayBuffers so
+// worker threads can operate on them in place.
+export class ParticleStore {
+    constructor(count = 0, { shared = false } = {}) {
+        this.shared = shared;
+        this.resize(count);
+    }
+
+    allocate(length, Type = Float32Array) {
+        return this.shared
+            ? new Type(new SharedArrayBuffer(length * Type.BYTES_PER_ELEMENT))
+            : new Type(length);
+    }
+
+    // Reallocates every array for `count` particles, dropping the old state
+    resize(count) {
+        this.count = count;
+        this.positions = this.allocate(count * 3);
+        this.velocities = this.allocate(count * 3, Float64Array);
+        this.accelerations = this.allocate(count * 3, Float64Array);
+        this.densities = this.allocate(count);
+        this.pressures = this.allocate(count);
+        this.colors = this.allocate(count * 3);
+        return this;
+    }
+
+    // A store over existing arrays, e.g. ones shared by another thread
+    static fromArrays(arrays) {
+        return Object.assign(Object.create(ParticleStore.prototype), arrays);
+    }
+
+    // The arrays and count, for sending to another thread
+    toArrays() {
+        const { count, shared, positions, velocities, accelerations, densities, pressures, colors } = this;
+        return { count, shared, positions, velocities, accelerations, densities, pressures, colors };
+    }
+
+    setPosition(i, x, y, z) {
+        this.positions[i * 3] = x;
+        this.positions[i * 3 + 1] = y;
+        this.positions[i * 3 + 2] = z;
+    }
+
+    setVelocity(i, x, y, z) {
+        this.velocities[i * 3] = x;
+        this.velocities[i * 3 + 1] = y;
+        this.velocities[i * 3 + 2] = z;
+    }
+
+    setColor(i, r, g, b) {
+        this.colors[i * 3] = r;
+        this.colors[i * 3 + 1] = g;
+        this.colors[i * 3 + 2] = b;
+    }
+
+    // Copies particle i's position into a { x, y, z } vector
+    readPosition(i, out) {
+        out.x = this.positions[i * 3];
+        out.y = this.positions[i * 3 + 1];
+        out.z = this.positions[i * 3 + 2];
+        return out;
+    }
+
+    readVelocity(i, out) {
+        out.x = this.velocities[i * 3];
+        out.y = this.velocities[i * 3 + 1];
+        out.z = this.velocities[i * 3 + 2];
+        return out;
+    }
+
+    // Moves every particle by the same offset and velocity change
+    translate(positionShift, velocityShift) {
+        for (let i = 0; i < this.count; i++) {
+            this.positions[i * 3] += positionShift.x;
+            this.positions[i * 3 + 1] += positionShift.y;
+            this.positions[i * 3 + 2] += positionShift.z;
+            this.velocities[i * 3] += velocityShift.x;
+            this.velocities[i * 3 + 1] += velocityShift.y;
+            this.velocities[i * 3 + 2] += velocityShift.z;
+        }
+    }
+}
@@ -0,0 +1,45 @@
+// Smoothing kernels used by the SPH solver (Müller et al. 2003).
+// All kernels have compact support: they vanish for r >= h.
+
+export function poly6(r2, h) {
+    const h2 = h * h;
+    if (r2 >= h2) return 0;
+    const diff = h2 - r2;
+    return 315 / (64 * Math.PI * Math.pow(h, 9)) * diff * diff * diff;
+}
+
+// Magnitude of the spiky kernel gradient. The gradient points along the
+// separation vector; the value returned here is |dW/dr| so callers can
+// scale the unit direction themselves.
+export function spikyGradient(r, h) {
+    if (r >= h) return 0;
+    const diff = h - r;
+    return 45 / (Math.PI * Math.pow(h, 6)) * diff * diff;
+}
+
+// Laplacian of the viscosity kernel
+export function viscosityLaplacian(r, h) {
+    if (r >= h) return 0;
+    return 45 / (Math.PI * Math.pow(h, 6)) * (h - r);
+}
+
+// Integrals over the half-space lying beyond a plane at distance d >= 0
+// from the particle. A solid surface acts as a half-space of fluid at
+// rest density, so these give its share of a particle's density and the
+// magnitude of its pressure push.
+
+// Fraction of the poly6 kernel's weight beyond the plane
+export function poly6HalfSpace(d, h) {
+    if (d >= h) return 0;
+    const s = d / h;
+    const s2 = s * s;
+    return 0.5 - 315 / 256 * s * (1 + s2 * (-4 / 3 + s2 * (6 / 5 + s2 * (-4 / 7 + s2 / 9))));
+}
+
+// Magnitude of the spiky kernel gradient integrated beyond the plane; the
+// integral points along the plane's normal
+export function spikyGradientHalfSpace(d, h) {
+    if (d >= h) return 0;
+    const diff = h - d;
+    return 1.5 * diff * diff * diff * diff * (h + 4 * d) / Math.pow(h, 6);
+}
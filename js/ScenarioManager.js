@@ -1,22 +1,67 @@
+import { BODY_TYPES } from './CelestialBody.js';
+import { forceFieldTypes } from './ForceFields.js';
+
 export class ScenarioManager {
     constructor() {
         this.currentScenario = null;
         this.seed = null;
     }
 
-    createScenario(config) {
+    createScenario(config, objects = [], forces = [], seed = this.generateSeed(), probes = []) {
         const scenario = {
-            seed: this.generateSeed(),
+            seed,
             parameters: {
                 ...config
             },
-            objects: [],
-            forces: []
+            objects: [...objects],
+            forces: [...forces],
+            probes: [...probes]
         };
         
         return this.encodeSeed(scenario);
     }
 
+    // Expands a base scenario over parameter values into one encoded
+    // scenario per run, as { run, replicate, values, scenario }.
+    // `sweep.grid` maps parameter names to value lists and takes every
+    // combination; `sweep.list` gives explicit parameter sets. Each
+    // combination is repeated `sweep.replicates` times with its own random
+    // seed, derived from the base seed so the ensemble is repeatable.
+    createSweep(base, sweep) {
+        const combinations = sweep.list ? sweep.list.map(values => ({ ...values })) : [{}];
+        if (!sweep.list) {
+            for (const [name, values] of Object.entries(sweep.grid || {})) {
+                if (!Array.isArray(values) || values.length === 0) {
+                    throw new Error(`Sweep values for ${name} must be a non-empty array`);
+                }
+                const expanded = [];
+                for (const combination of combinations) {
+                    for (const value of values) {
+                        expanded.push({ ...combination, [name]: value });
+                    }
+                }
+                combinations.splice(0, combinations.length, ...expanded);
+            }
+        }
+
+        const baseSeed = base.seed ?? this.generateSeed();
+        const replicates = sweep.replicates || 1;
+        const runs = [];
+        combinations.forEach((values, index) => {
+            for (let replicate = 0; replicate < replicates; replicate++) {
+                const seed = replicates > 1 ? `${baseSeed}-${replicate}` : baseSeed;
+                runs.push({
+                    run: index,
+                    replicate,
+                    values,
+                    scenario: this.createScenario({ ...base.parameters, ...values },
+                        base.objects || [], base.forces || [], seed, base.probes || [])
+                });
+            }
+        });
+        return runs;
+    }
+
     loadScenario(seed) {
         try {
             const scenarioData = this.decodeSeed(seed);
@@ -81,6 +126,112 @@ export class ScenarioManager {
         if (!Array.isArray(scenario.forces)) {
             throw new Error('Invalid forces: must be an array');
         }
+
+        this.validateObjects(scenario.objects);
+        this.validateForces(scenario.forces);
+
+        // Tide gauges came later; older scenarios have none
+        if (scenario.probes !== undefined) {
+            if (!Array.isArray(scenario.probes)) {
+                throw new Error('Invalid probes: must be an array');
+            }
+            this.validateProbes(scenario.probes);
+        }
+    }
+
+    validateProbes(probes) {
+        const names = new Set();
+        probes.forEach((probe, index) => {
+            const label = `Invalid probe ${probe && probe.name ? `"${probe.name}"` : index}`;
+
+            if (!probe || typeof probe !== 'object') {
+                throw new Error(`${label}: must be an object`);
+            }
+            if (typeof probe.name !== 'string' || probe.name === '') {
+                throw new Error(`${label}: name must be a non-empty string`);
+            }
+            if (names.has(probe.name)) {
+                throw new Error(`${label}: duplicate name`);
+            }
+            names.add(probe.name);
+            if (!(Math.abs(probe.latitude) <= 90)) {
+                throw new Error(`${label}: latitude must be a number in [-90, 90]`);
+            }
+            if (!Number.isFinite(probe.longitude)) {
+                throw new Error(`${label}: longitude must be a number`);
+            }
+            if (probe.radius !== undefined && !(probe.radius > 0)) {
+                throw new Error(`${label}: radius must be a positive number`);
+            }
+        });
+    }
+
+    validateForces(forces) {
+        forces.forEach((force, index) => {
+            if (!force || typeof force !== 'object') {
+                throw new Error(`Invalid force ${index}: must be an object`);
+            }
+            const type = forceFieldTypes[force.type];
+            if (!type) {
+                throw new Error(`Invalid force ${index}: unknown type "${force.type}"`);
+            }
+
+            for (const field of type.fields) {
+                const value = force[field.name];
+                if (value === undefined) continue;
+                const valid = field.type === 'vector'
+                    ? Array.isArray(value) && value.length === 3 && value.every(Number.isFinite)
+                    : field.type === 'number' ? Number.isFinite(value) : typeof value === 'string';
+                if (!valid) {
+                    throw new Error(`Invalid force ${index}: ${field.name} must be a ${field.type}`);
+                }
+            }
+        });
+    }
+
+    validateObjects(objects) {
+        const knownIds = new Set();
+
+        objects.forEach((object, index) => {
+            const label = `Invalid object ${object && object.id ? `"${object.id}"` : index}`;
+
+            if (!object || typeof object !== 'object') {
+                throw new Error(`${label}: must be an object`);
+            }
+            if (!BODY_TYPES.includes(object.type)) {
+                throw new Error(`${label}: type must be one of ${BODY_TYPES.join(', ')}`);
+            }
+            if (!(object.mass > 0) || !(object.radius > 0)) {
+                throw new Error(`${label}: mass and radius must be positive numbers`);
+            }
+
+            for (const name of ['obliquity', 'axisAzimuth']) {
+                if (object[name] !== undefined && !Number.isFinite(object[name])) {
+                    throw new Error(`${label}: ${name} must be a number`);
+                }
+            }
+
+            if (object.orbit) {
+                // Parents must come first so orbits can be resolved in order
+                if (!knownIds.has(object.orbit.parent)) {
+                    throw new Error(`${label}: orbit parent must be an earlier object`);
+                }
+                if (!(object.orbit.semiMajorAxis > 0)) {
+                    throw new Error(`${label}: orbit semiMajorAxis must be positive`);
+                }
+                const eccentricity = object.orbit.eccentricity || 0;
+                if (eccentricity < 0 || eccentricity >= 1) {
+                    throw new Error(`${label}: orbit eccentricity must be in [0, 1)`);
+                }
+            }
+
+            if (object.id !== undefined) {
+                if (knownIds.has(object.id)) {
+                    throw new Error(`${label}: duplicate id`);
+                }
+                knownIds.add(object.id);
+            }
+        });
     }
 
     createDefaultScenario() {
@@ -94,7 +245,8 @@ export class ScenarioManager {
                 viscosity: 1.0
             },
             objects: [],
-            forces: []
+            forces: [],
+            probes: []
         };
     }
 }
@@ -0,0 +1,1417 @@
+import { Vector3 } from './Vector3.js';
+import { poly6, spikyGradient, viscosityLaplacian, poly6HalfSpace, spikyGradientHalfSpace } from './SPHKernels.js';
+import { getIntegrator } from './Integrators.js';
+import { orbitalPosition, orbitalVelocity } from './KeplerOrbit.js';
+import { CelestialBody } from './CelestialBody.js';
+import { createForceField, applyForceFields } from './ForceFields.js';
+import { SeededRandom } from './SeededRandom.js';
+import { SpatialHash } from './SpatialHash.js';
+import { ParticleStore } from './ParticleStore.js';
+import { BarnesHutTree } from './BarnesHut.js';
+import { TideGauge } from './TideGauges.js';
+
+// Legacy planet/moon/sun parameters and the body fields they drive, with
+// an optional conversion. Planet parameters apply to the body hosting the
+// fluid, moon and sun parameters to the first moon and sun, so the UI
+// keeps working for data-driven scenarios.
+const BODY_PARAMETERS = {
+    planetMass: ['planet', 'mass'],
+    planetRadius: ['planet', 'radius'],
+    planetRotationSpeed: ['planet', 'rotationSpeed'],
+    planetAxialTilt: ['planet', 'obliquity'],
+    planetTiltAzimuth: ['planet', 'axisAzimuth'],
+    moonMass: ['moon', 'mass'],
+    moonRadius: ['moon', 'radius'],
+    moonRotationSpeed: ['moon', 'rotationSpeed'],
+    moonAxialTilt: ['moon', 'obliquity'],
+    moonOrbitRadius: ['moon', 'orbit.semiMajorAxis'],
+    moonEccentricity: ['moon', 'orbit.eccentricity'],
+    moonInclination: ['moon', 'orbit.inclination'],
+    moonArgumentOfPeriapsis: ['moon', 'orbit.argumentOfPeriapsis'],
+    moonAscendingNode: ['moon', 'orbit.ascendingNode'],
+    moonInitialAngle: ['moon', 'orbit.initialMeanAnomaly'],
+    moonOrbitalSpeed: ['moon', 'orbit.meanMotion'],
+    sunMass: ['sun', 'mass'],
+    sunRadius: ['sun', 'radius'],
+    sunDistance: ['sun', 'orbit.semiMajorAxis'],
+    sunPeriod: ['sun', 'orbit.meanMotion', period => 2 * Math.PI / period],
+    sunInitialAngle: ['sun', 'orbit.initialMeanAnomaly']
+};
+
+// Floats per body in exportBodyState()
+const BODY_STATE_STRIDE = 8;
+
+// The physics of the simulator: parameters, bodies, fluid particles and
+// the time stepping. It has no DOM or WebGL dependency, so it runs in the
+// browser, in Node and in workers alike; FluidSimulator renders it.
+//
+// Views that mirror the core register callbacks:
+//   onBodiesChange()          - the set of bodies was replaced
+//   onBodyShapeChange(body)   - a body's radius or orbit elements changed
+//   onParticlesReset()        - the particle list was rebuilt
+//   onProbesChange()          - the tide gauges were replaced
+export class SimulationCore {
+    constructor() {
+        // Store default parameters
+        this.defaultParameters = {
+            // Planet parameters
+            planetRadius: 5.0,
+            planetMass: 1000.0,
+            planetRotationSpeed: 0.0,
+            planetRotationAngle: 0,
+            planetAxialTilt: 0.0,            // Obliquity: tilt of the spin axis away from +Y (rad)
+            planetTiltAzimuth: 0.0,          // Direction in the XZ plane the axis leans towards (rad)
+            
+            // Moon parameters
+            moonRadius: 1.0,
+            moonMass: 100.0,
+            moonOrbitRadius: 15.0,           // Semi-major axis of the orbit
+            moonEccentricity: 0.0,
+            moonInclination: 0.0,            // Tilt of the orbit against the XZ plane (rad)
+            moonArgumentOfPeriapsis: 0.0,    // Angle from ascending node to periapsis (rad)
+            moonAscendingNode: 0.0,          // Longitude of the ascending node (rad)
+            moonInitialAngle: 0,             // Mean anomaly at t = 0 (rad)
+            moonOrbitalSpeed: 0.5,           // Mean motion (rad/s)
+            moonRotationSpeed: 0.0,
+            moonRotationAngle: 0,
+            moonAxialTilt: 0.0,
+            orbitMode: 'kepler',             // 'kepler' follows the prescribed orbit, 'nbody' integrates mutual gravity
+
+            // Optional distant sun for solar tides, on an apparent orbit
+            // around the planet in the XZ plane
+            sunEnabled: false,
+            sunMass: 4.4e6,                  // With the defaults its tide is about half the moon's
+            sunRadius: 20.0,
+            sunDistance: 400.0,
+            sunPeriod: 60.0,                 // Apparent orbital period (s); sets the spring-neap cycle with the moon
+            sunInitialAngle: 0,              // Mean anomaly at t = 0 (rad)
+            sunLight: true,                  // Light the scene from the sun
+            
+            // Fluid parameters
+            particleCount: 1000,
+            particleSize: 0.1,
+            fluidHeight: 0.5,    // Height of fluid above planet surface
+            fluidSpread: 0.8,    // How much of planet surface is covered (0-1)
+            
+            // Physics parameters
+            gravity: -9.81,
+            viscosity: 1.0,          // Kinematic viscosity of the fluid
+            // Global velocity damping rate (1/s), independent of viscosity.
+            // 0.3/s is what the old default of 4.0 per frame-scaled step
+            // (0.016 * 0.08 s per 60 Hz frame) amounted to in real seconds.
+            damping: 0.3,
+            density: 1000.0,         // Rest density the equation of state pushes towards
+            pressureStiffness: 200.0, // Stiffness of the equation of state
+            smoothingRadius: 1.0,     // SPH kernel support radius
+            gravitationalConstant: 6.67430e-11,
+            timeScale: 1.0,          // Simulated seconds per wall-clock second
+            fixedTimeStep: 1 / 120,  // Physics step size in simulated seconds
+            maxSubsteps: 8,          // Maximum physics steps per rendered frame
+            integrator: 'leapfrog',    // Time integration scheme, see Integrators.js
+            adaptiveTimeStep: true,  // Split physics steps to respect the stability limits below
+            courantFactor: 0.4,      // Fraction of the CFL/force/viscosity limits actually used
+            maxAdaptiveSubsteps: 64, // Upper bound on adaptive splits of one physics step
+            scale: 1.0,
+            
+            // Surface tension parameters
+            surfaceTensionStrength: 0.8,    // Strength of surface tension between particles
+            surfaceTensionRadius: 1.0,      // Radius within which particles affect each other
+            cohesionStrength: 0.5,          // Strength of particle cohesion
+            tensionResistance: 0.3,         // Resistance to stretching/separation
+
+            // Self-gravity of the fluid, see BarnesHut.js
+            selfGravity: false,             // Let particles attract each other (and the bodies in N-body mode)
+            fluidMass: 100.0,               // Mass of the whole fluid in body mass units, for its gravity and contact momentum
+            selfGravityOpeningAngle: 0.5,   // Cells smaller than this angle act as point masses; 0 sums every pair
+            selfGravitySoftening: 0.3,      // Plummer softening length of particle gravity
+
+            // Contact with solid body surfaces; bodies may override both
+            surfaceRestitution: 0.1,        // Fraction of the impact speed bounced back along the normal
+            surfaceFriction: 0.3,           // Coulomb friction coefficient against the surface
+            surfaceDrag: 0.2,               // Rate (1/s) at which the bottom layer is dragged along with the surface
+
+            // Solve the fluid in the frame turning with the fluid host
+            rotatingFrame: false
+        };
+
+        // Clone default parameters for current use
+        this.parameters = { ...this.defaultParameters };
+
+        this.onBodiesChange = null;
+        this.onBodyShapeChange = null;
+        this.onParticlesReset = null;
+        this.onProbesChange = null;
+
+        // Fluid particle state, see ParticleStore.js
+        this.particles = new ParticleStore();
+
+        this.bodies = [];
+        this.setBodies([]);
+
+        // Extra force fields from the scenario, see ForceFields.js
+        this.forceFields = [];
+
+        // Tide gauges from the scenario, see TideGauges.js
+        this.tideGauges = [];
+        this.tideGaugeLimit = Infinity;  // Samples each gauge keeps
+        this._gaugeDirection = new Vector3();
+
+        // Every random draw goes through this generator so a scenario seed
+        // reproduces the same layout and run
+        this.seed = 'default';
+        this.random = new SeededRandom(this.seed);
+
+        // Neighbor search shared by the SPH terms and force fields
+        this.neighborSearch = new SpatialHash();
+
+        // Optional ForcePool that splits the particle passes across workers
+        this.forcePool = null;
+
+        // Octree for the fluid's self-gravity, rebuilt every evaluation
+        this.selfGravityTree = new BarnesHutTree();
+
+        // Co-rotating frame of the current substep, null in inertial mode.
+        // See enterRotatingFrame().
+        this.frame = null;
+
+        // Pull of tidal bodies on the fluid host, see updateTidalReference()
+        this.tidalReference = new Vector3();
+
+        this.initializeParticles();
+
+        this.frameCount = 0;
+        this.simulationTime = 0;     // Simulated seconds since the last reset
+        this.timeAccumulator = 0;    // Simulated time owed to the physics clock
+        this.effectiveTimeStep = this.parameters.fixedTimeStep; // Last adaptive substep size
+        this.clampedSubsteps = 0;    // Substeps held at the maxAdaptiveSubsteps floor, see step()
+        this._tempVec1 = new Vector3();
+        this._tempVec2 = new Vector3();
+        this._tempVec3 = new Vector3();
+        this._tempVec4 = new Vector3();
+    }
+
+    // The system described by the legacy planet/moon/sun parameters, used
+    // when a scenario doesn't define its own objects
+    createDefaultBodyDefinitions() {
+        const p = this.parameters;
+        const definitions = [
+            {
+                id: 'planet',
+                type: 'planet',
+                mass: p.planetMass,
+                radius: p.planetRadius,
+                rotationSpeed: p.planetRotationSpeed,
+                rotationAngle: p.planetRotationAngle,
+                obliquity: p.planetAxialTilt,
+                axisAzimuth: p.planetTiltAzimuth,
+                fluid: true
+            },
+            {
+                id: 'moon',
+                type: 'moon',
+                mass: p.moonMass,
+                radius: p.moonRadius,
+                rotationSpeed: p.moonRotationSpeed,
+                rotationAngle: p.moonRotationAngle,
+                obliquity: p.moonAxialTilt,
+                gravityMultiplier: 5.0,  // Tides on the fluid only, not the planet
+                orbit: {
+                    parent: 'planet',
+                    semiMajorAxis: p.moonOrbitRadius,
+                    eccentricity: p.moonEccentricity,
+                    inclination: p.moonInclination,
+                    argumentOfPeriapsis: p.moonArgumentOfPeriapsis,
+                    ascendingNode: p.moonAscendingNode,
+                    meanAnomaly: p.moonInitialAngle,
+                    meanMotion: p.moonOrbitalSpeed
+                }
+            }
+        ];
+        if (p.sunEnabled) {
+            definitions.push({
+                id: 'sun',
+                type: 'sun',
+                mass: p.sunMass,
+                radius: p.sunRadius,
+                orbit: {
+                    parent: 'planet',
+                    semiMajorAxis: p.sunDistance,
+                    meanAnomaly: p.sunInitialAngle,
+                    meanMotion: 2 * Math.PI / p.sunPeriod
+                }
+            });
+        }
+        return definitions;
+    }
+
+    // Replaces all bodies with the given scenario object definitions. An
+    // empty list falls back to the default planet and moon.
+    setBodies(definitions) {
+        const previousPrimary = this.primaryBody;
+
+        this.usesDefaultBodies = !(definitions && definitions.length > 0);
+        const source = this.usesDefaultBodies ? this.createDefaultBodyDefinitions() : definitions;
+        this.bodies = source.map((definition, index) =>
+            new CelestialBody({ id: `body${index}`, ...definition }));
+
+        this.bodiesById = new Map(this.bodies.map(body => [body.id, body]));
+        for (const body of this.bodies) {
+            if (body.orbit) {
+                body.parent = this.bodiesById.get(body.orbit.parent) || null;
+            }
+        }
+
+        // The fluid sits on the flagged body, else the first planet
+        this.primaryBody = this.bodies.find(body => body.hostsFluid) ||
+                           this.bodies.find(body => body.type === 'planet') ||
+                           this.bodies[0];
+        this.primaryMoon = this.bodies.find(body => body.type === 'moon') || null;
+        this.sun = this.bodies.find(body => body.type === 'sun') || null;
+
+        // Keep existing fluid attached to the new primary body
+        if (previousPrimary) {
+            this.primaryBody.position.copy(previousPrimary.position);
+            this.primaryBody.velocity.copy(previousPrimary.velocity);
+        }
+        this.initializeBodies();
+
+        if (this.onBodiesChange) {
+            this.onBodiesChange();
+        }
+    }
+
+    scaledGravitationalConstant() {
+        return this.parameters.gravitationalConstant * 1e10;
+    }
+
+    // Angular rate of a body around its parent: the prescribed value if the
+    // scenario gives one, else the Keplerian rate for the two masses
+    getMeanMotion(body, prescribed = true) {
+        if (prescribed && body.orbit.meanMotion !== null) {
+            return body.orbit.meanMotion;
+        }
+        return Math.sqrt(this.scaledGravitationalConstant() * (body.parent.mass + body.mass) /
+                         Math.pow(body.orbit.semiMajorAxis, 3));
+    }
+
+    // Puts every orbiting body on its Keplerian orbit `timeOffset` seconds
+    // after its current mean anomaly. Parents are listed first, so walking
+    // the list in order places them before their satellites.
+    placeBodiesOnOrbits(timeOffset = 0, prescribed = true) {
+        const offset = this._tempOrbitOffset || (this._tempOrbitOffset = new Vector3());
+
+        for (const body of this.bodies) {
+            if (!body.parent) continue;
+
+            const elements = body.getOrbitElements();
+            const meanMotion = this.getMeanMotion(body, prescribed);
+            const meanAnomaly = body.orbit.meanAnomaly + meanMotion * timeOffset;
+
+            orbitalPosition(elements, meanAnomaly, offset);
+            body.position.copy(body.parent.position).add(offset);
+            orbitalVelocity(elements, meanAnomaly, meanMotion, offset);
+            body.velocity.copy(body.parent.velocity).add(offset);
+        }
+    }
+
+    // Acceleration of a body moving along its prescribed orbit, including
+    // that of the parents it is carried along with. A Keplerian ellipse
+    // traversed at mean motion n is the path of a point pulled towards its
+    // focus by an inverse square force with n²a³ in place of GM.
+    orbitalAcceleration(body, out) {
+        const offset = this._tempOrbitOffset || (this._tempOrbitOffset = new Vector3());
+        out.set(0, 0, 0);
+
+        for (let current = body; current.parent; current = current.parent) {
+            const meanMotion = this.getMeanMotion(current);
+            const semiMajorAxis = current.orbit.semiMajorAxis;
+            offset.copy(current.position).sub(current.parent.position);
+            const distance = offset.length();
+            if (distance === 0) continue;
+            const pull = meanMotion * meanMotion * Math.pow(semiMajorAxis / distance, 3);
+            out.addScaledVector(offset, -pull);
+        }
+        return out;
+    }
+
+    // Places all bodies for the current orbit mode. In N-body mode orbital
+    // elements become initial conditions with speeds set by the actual
+    // masses, and the system is moved into its barycentric frame. Any fluid
+    // is carried along so it keeps its position and velocity relative to the
+    // primary body.
+    initializeBodies() {
+        const primary = this.primaryBody;
+        const previousPosition = primary.position.clone();
+        const previousVelocity = primary.velocity.clone();
+
+        for (const body of this.bodies) {
+            body.position.copy(body.initialPosition);
+            body.velocity.copy(body.initialVelocity);
+            if (body.orbit) {
+                body.orbit.meanAnomaly = body.orbit.initialMeanAnomaly;
+            }
+        }
+
+        if (this.parameters.orbitMode === 'nbody') {
+            this.placeBodiesOnOrbits(0, false);
+
+            // Remove the barycenter's position and drift
+            const totalMass = this.bodies.reduce((sum, body) => sum + body.mass, 0);
+            const centerOfMass = new Vector3();
+            const momentum = new Vector3();
+            for (const body of this.bodies) {
+                centerOfMass.addScaledVector(body.position, body.mass / totalMass);
+                momentum.addScaledVector(body.velocity, body.mass / totalMass);
+            }
+            for (const body of this.bodies) {
+                body.position.sub(centerOfMass);
+                body.velocity.sub(momentum);
+            }
+        } else {
+            // Bodies without an orbit stay put
+            for (const body of this.bodies) {
+                if (!body.parent) body.velocity.set(0, 0, 0);
+            }
+            this.placeBodiesOnOrbits(0);
+        }
+
+        this.particles.translate(
+            primary.position.clone().sub(previousPosition),
+            primary.velocity.clone().sub(previousVelocity));
+    }
+
+    // Body state packed BODY_STATE_STRIDE floats per body (position,
+    // velocity, rotation angle, mean anomaly), for handing the dynamic
+    // state of the bodies to another copy of the core
+    exportBodyState() {
+        const state = new Float64Array(this.bodies.length * BODY_STATE_STRIDE);
+        this.bodies.forEach((body, i) => {
+            const k = i * BODY_STATE_STRIDE;
+            state[k] = body.position.x;
+            state[k + 1] = body.position.y;
+            state[k + 2] = body.position.z;
+            state[k + 3] = body.velocity.x;
+            state[k + 4] = body.velocity.y;
+            state[k + 5] = body.velocity.z;
+            state[k + 6] = body.rotationAngle;
+            state[k + 7] = body.orbit ? body.orbit.meanAnomaly : 0;
+        });
+        return state;
+    }
+
+    importBodyState(state) {
+        this.bodies.forEach((body, i) => {
+            const k = i * BODY_STATE_STRIDE;
+            body.position.set(state[k], state[k + 1], state[k + 2]);
+            body.velocity.set(state[k + 3], state[k + 4], state[k + 5]);
+            body.rotationAngle = state[k + 6];
+            if (body.orbit) {
+                body.orbit.meanAnomaly = state[k + 7];
+            }
+        });
+    }
+
+    // Mutual gravity between all massive bodies, plus the pull of the
+    // fluid when it has self-gravity. Bodies attract each other with their
+    // plain masses; gravityMultiplier only applies to the fluid.
+    computeBodyAccelerations() {
+        for (const body of this.bodies) {
+            body.acceleration.set(0, 0, 0);
+            for (const other of this.bodies) {
+                if (other === body) continue;
+                body.acceleration.add(this.calculateGravitationalForce(
+                    body.position, other.mass, other.position, 1.0));
+            }
+            if (this.parameters.selfGravity) {
+                if (this.frame) {
+                    // The tree holds frame coordinates
+                    const position = this._tempVec1.copy(body.position);
+                    const pull = this._tempVec2.set(0, 0, 0);
+                    this.addSelfGravity(this.pointToFrame(position), pull);
+                    body.acceleration.add(pull.applyAxisAngle(this.primaryBody.spinAxis, this.frame.angle));
+                } else {
+                    this.addSelfGravity(body.position, body.acceleration);
+                }
+            }
+        }
+    }
+
+    // Mass of one particle in body mass units. (particleMass is the SPH
+    // mass, calibrated for the rest density instead.)
+    particleGravityMass() {
+        return this.particles.count > 0 ? this.parameters.fluidMass / this.particles.count : 0;
+    }
+
+    buildSelfGravityTree() {
+        const { positions, count } = this.particles;
+        this.selfGravityTree.build(positions, count, this.particleGravityMass());
+    }
+
+    // Adds the fluid's gravity at a point to `out`, from the current tree
+    addSelfGravity(position, out) {
+        const pull = this._selfGravityForce || (this._selfGravityForce = new Vector3());
+        pull.set(0, 0, 0);
+        this.selfGravityTree.accelerationAt(position.x, position.y, position.z,
+            this.parameters.selfGravityOpeningAngle, this.parameters.selfGravitySoftening, pull);
+        out.addScaledVector(pull, this.scaledGravitationalConstant());
+    }
+
+    // Replaces all force fields with the given scenario force definitions
+    setForces(definitions) {
+        this.forceFields = definitions.map(definition => createForceField(definition));
+    }
+
+    addForce(definition) {
+        this.forceFields.push(createForceField(definition));
+        return this.forceFields.length - 1;
+    }
+
+    removeForce(index) {
+        this.forceFields.splice(index, 1);
+    }
+
+    setForceValue(index, name, value) {
+        const field = this.forceFields[index];
+        if (field && name in field) {
+            field[name] = value;
+        }
+    }
+
+    // Force definitions in the same shape scenarios store them
+    getForces() {
+        return this.forceFields.map(field => ({ ...field }));
+    }
+
+    // Replaces the tide gauges. Every gauge starts a fresh record so the
+    // series stay aligned step for step.
+    setProbes(definitions) {
+        this.tideGauges = definitions.map(definition => new TideGauge(definition, this.tideGaugeLimit));
+        if (this.onProbesChange) {
+            this.onProbesChange();
+        }
+    }
+
+    getProbes() {
+        return this.tideGauges.map(gauge => gauge.toDefinition());
+    }
+
+    // Gauges share one grid over the positions the step ended with; the
+    // neighbor lists of the last evaluation are left as they were
+    recordTideGauges() {
+        if (this.tideGauges.length === 0) return;
+
+        const cutoff = Math.max(this.parameters.smoothingRadius, this.parameters.surfaceTensionRadius);
+        const grid = this.neighborSearch.build(this.particles.positions, this.particles.count, cutoff);
+        for (const gauge of this.tideGauges) {
+            gauge.record(this.simulationTime, gauge.measure(this, grid, this._gaugeDirection));
+        }
+    }
+
+    // Applies a legacy planet/moon/sun parameter to the body it drives
+    applyBodyParameter(name, parameterValue) {
+        const [role, field, convert] = BODY_PARAMETERS[name];
+        const body = { planet: this.primaryBody, moon: this.primaryMoon, sun: this.sun }[role];
+        if (!body) return;
+        const value = convert ? convert(parameterValue) : parameterValue;
+
+        if (field.startsWith('orbit.')) {
+            if (!body.orbit) return;
+            body.orbit[field.slice('orbit.'.length)] = value;
+        } else {
+            body[field] = value;
+        }
+
+        if (field === 'obliquity' || field === 'axisAzimuth') {
+            body.updateSpinAxis();
+        }
+
+        if (field === 'radius' || field.startsWith('orbit.')) {
+            if (this.onBodyShapeChange) {
+                this.onBodyShapeChange(body);
+            }
+        }
+
+        if (field.startsWith('orbit.')) {
+            // Changing the shape of a prescribed orbit keeps the moon's phase;
+            // a new epoch or an N-body run starts over from the elements
+            if (this.parameters.orbitMode === 'nbody' || field === 'orbit.initialMeanAnomaly') {
+                this.initializeBodies();
+            } else {
+                this.placeBodiesOnOrbits(0);
+            }
+        }
+        // Reset rotation angles when speeds are changed to zero
+        else if (field === 'rotationSpeed' && value === 0) {
+            body.rotationAngle = 0;
+        }
+    }
+
+    setSeed(seed) {
+        this.seed = String(seed);
+        this.random = new SeededRandom(this.seed);
+    }
+
+    // Loads a decoded scenario: parameters first, then its bodies and
+    // forces, then a fresh fluid layer on the new primary body
+    applyScenario(scenario, fallbackSeed = this.seed) {
+        Object.entries(scenario.parameters).forEach(([name, value]) => {
+            this.setParameter(name, value);
+        });
+
+        this.setSeed(scenario.seed ?? fallbackSeed);
+        this.setBodies(scenario.objects);
+        this.setForces(scenario.forces);
+        this.setProbes(scenario.probes || []);
+        this.initializeParticles();
+
+        this.simulationTime = 0;
+        this.timeAccumulator = 0;
+        this.clampedSubsteps = 0;
+    }
+
+    // Hands the particle passes to a ForcePool (or back to this thread with
+    // null). Pooled particle arrays live in shared memory.
+    setForcePool(pool) {
+        this.forcePool = pool;
+        this.particles = new ParticleStore(0, { shared: Boolean(pool) });
+        this.initializeParticles();
+    }
+
+    initializeParticles() {
+        // Restart the generator so the same seed always gives the same layout
+        this.random = new SeededRandom(this.seed);
+
+        const store = this.particles.resize(this.parameters.particleCount);
+        const primary = this.primaryBody;
+
+        // Calculate the area of planet surface to cover based on fluidSpread
+        const maxPhi = Math.PI * this.parameters.fluidSpread;
+        const phiStart = (Math.PI - maxPhi) / 2; // Center the fluid coverage
+
+        // Latitudes are measured from the spin axis, so the coverage is
+        // centered on the (possibly tilted) equator
+        const pole = primary.spinAxis;
+        const east = new Vector3(0, 0, 1);
+        if (Math.abs(pole.z) > 0.9) {
+            east.set(1, 0, 0);
+        }
+        east.copy(pole.clone().cross(east).normalize());
+        const north = pole.clone().cross(east);
+
+        // The ocean fills the shell between the surface and fluidHeight
+        // above it, uniformly by volume
+        const innerCubed = primary.radius ** 3;
+        const outerCubed = (primary.radius + this.parameters.fluidHeight) ** 3;
+
+        for (let i = 0; i < this.parameters.particleCount; i++) {
+            // Generate evenly distributed spherical coordinates
+            const theta = this.random.next() * 2 * Math.PI;  // Longitude (0 to 2π)
+            const phi = phiStart + (this.random.next() * maxPhi);  // Latitude (controlled by fluidSpread)
+            
+            // Depth within the layer
+            const radius = Math.cbrt(innerCubed + this.random.next() * (outerCubed - innerCubed));
+            const across = radius * Math.sin(phi) * Math.cos(theta);
+            const along = radius * Math.sin(phi) * Math.sin(theta);
+            const up = radius * Math.cos(phi);
+            const x = across * east.x + along * north.x + up * pole.x;
+            const y = across * east.y + along * north.y + up * pole.y;
+            const z = across * east.z + along * north.z + up * pole.z;
+
+            // Create particle at rest relative to the planet, spin included
+            const spin = pole;
+            const rate = primary.rotationSpeed;
+            store.setPosition(i, x + primary.position.x, y + primary.position.y, z + primary.position.z);
+            store.setVelocity(i,
+                primary.velocity.x + rate * (spin.y * z - spin.z * y),
+                primary.velocity.y + rate * (spin.z * x - spin.x * z),
+                primary.velocity.z + rate * (spin.x * y - spin.y * x));
+
+            // Blue color for water particles
+            store.setColor(i, 0.0, 0.5, 1.0);
+        }
+
+        this.calibrateParticleMass();
+
+        if (this.onParticlesReset) {
+            this.onParticlesReset();
+        }
+    }
+
+    // Advances the simulation by one rendered frame. Wall-clock time is
+    // converted to simulated time through timeScale and consumed in fixed
+    // physics steps, so results do not depend on the display refresh rate.
+    // Returns the number of physics steps taken.
+    update(frameDeltaSeconds) {
+        const fixedTimeStep = this.parameters.fixedTimeStep;
+        this.timeAccumulator += frameDeltaSeconds * this.parameters.timeScale;
+
+        let substeps = 0;
+        while (this.timeAccumulator >= fixedTimeStep && substeps < this.parameters.maxSubsteps) {
+            this.step(fixedTimeStep);
+            this.timeAccumulator -= fixedTimeStep;
+            substeps++;
+        }
+
+        // If we fell behind, drop the backlog instead of trying to catch up
+        // next frame; the simulation slows down but stays deterministic.
+        if (substeps === this.parameters.maxSubsteps) {
+            this.timeAccumulator = Math.min(this.timeAccumulator, fixedTimeStep);
+        }
+
+        return substeps;
+    }
+
+    // Advances by one fixed physics step, splitting it into as many equal
+    // substeps as the current stability limit requires. The split is capped
+    // at maxAdaptiveSubsteps; a substep that would have needed to be shorter
+    // still runs at the cap and is counted in clampedSubsteps, since it is
+    // longer than the solve can stably take.
+    step(deltaTime) {
+        let remaining = deltaTime;
+        let substeps = 0;
+
+        while (remaining > 1e-12) {
+            let substepDelta = remaining;
+
+            if (this.parameters.adaptiveTimeStep) {
+                const minDelta = deltaTime / this.parameters.maxAdaptiveSubsteps;
+                const limit = this.computeStableTimeStep();
+                if (limit < minDelta) {
+                    this.clampedSubsteps++;
+                }
+                const stableDelta = Math.max(minDelta, limit);
+                // Spread the remaining time evenly so we never end on a sliver
+                substepDelta = remaining / Math.max(1, Math.ceil(remaining / stableDelta - 1e-9));
+            }
+
+            this.substep(substepDelta);
+            this.effectiveTimeStep = substepDelta;
+            remaining -= substepDelta;
+            substeps++;
+        }
+
+        this.recordTideGauges();
+        return substeps;
+    }
+
+    // Largest time step that keeps the explicit solve stable: a CFL limit on
+    // particle speed plus the speed of sound of the equation of state, a
+    // force limit on peak acceleration, and the viscous diffusion limit, all
+    // relative to the kernel radius.
+    computeStableTimeStep() {
+        const h = this.parameters.smoothingRadius;
+        let maxSpeedSq = 0;
+        let maxAccelerationSq = 0;
+
+        const { count, velocities, accelerations } = this.particles;
+        for (let k = 0; k < count * 3; k += 3) {
+            maxSpeedSq = Math.max(maxSpeedSq, velocities[k] * velocities[k] +
+                velocities[k + 1] * velocities[k + 1] + velocities[k + 2] * velocities[k + 2]);
+            maxAccelerationSq = Math.max(maxAccelerationSq, accelerations[k] * accelerations[k] +
+                accelerations[k + 1] * accelerations[k + 1] + accelerations[k + 2] * accelerations[k + 2]);
+        }
+
+        const soundSpeed = Math.sqrt(this.parameters.pressureStiffness);
+        let stableDelta = h / (Math.sqrt(maxSpeedSq) + soundSpeed);
+
+        if (maxAccelerationSq > 0) {
+            stableDelta = Math.min(stableDelta, Math.sqrt(h / Math.sqrt(maxAccelerationSq)));
+        }
+        if (this.parameters.viscosity > 0) {
+            stableDelta = Math.min(stableDelta, 0.125 * h * h / this.parameters.viscosity);
+        }
+
+        return this.parameters.courantFactor * stableDelta;
+    }
+
+    substep(deltaTime) {
+        if (this.parameters.rotatingFrame) {
+            this.enterRotatingFrame();
+        }
+
+        // Update rotations
+        for (const body of this.bodies) {
+            body.rotationAngle += body.rotationSpeed * deltaTime;
+        }
+
+        // Advance particles with the selected integration scheme
+        getIntegrator(this.parameters.integrator).step(this, deltaTime);
+        if (this.parameters.orbitMode === 'nbody') {
+            this.unpackBodyState();
+        }
+
+        if (this.frame) {
+            if (this.parameters.orbitMode !== 'nbody') {
+                this.placeBodiesOnOrbits(deltaTime);
+            }
+            this.leaveRotatingFrame(deltaTime);
+        }
+
+        // Keep particles out of the solid bodies
+        this.resolveCollisions();
+
+        if (this.parameters.orbitMode !== 'nbody') {
+            // Update orbital positions
+            for (const body of this.bodies) {
+                if (body.parent) {
+                    body.orbit.meanAnomaly += this.getMeanMotion(body) * deltaTime;
+                }
+            }
+            this.placeBodiesOnOrbits(0);
+        }
+
+        this.frameCount = (this.frameCount || 0) + 1;
+        this.simulationTime += deltaTime;
+    }
+
+    // With rotatingFrame set, each substep solves the fluid in a frame
+    // centered on the primary body and spinning with it, where the rotation
+    // shows up as explicit Coriolis and centrifugal accelerations and
+    // damping acts on motion relative to the planet. The particle arrays
+    // hold frame coordinates only while the integrator runs; outside a
+    // substep they are always inertial.
+    //
+    // Frame coordinates are x' = R(-angle)(x - c) and
+    // v' = R(-angle)(v - v_c) - spin × x', with c and v_c the position and
+    // velocity of the primary body and R a rotation about its spin axis.
+    enterRotatingFrame() {
+        const primary = this.primaryBody;
+        this.frame = this.frameState();
+        this.frame.startAngle = primary.rotationAngle;
+        this.frame.angle = primary.rotationAngle;
+        this.frame.acceleration.set(0, 0, 0);
+
+        const store = this.particles;
+        const position = this._tempVec1;
+        const velocity = this._tempVec2;
+        for (let i = 0; i < store.count; i++) {
+            store.readPosition(i, position);
+            store.readVelocity(i, velocity);
+            this.pointToFrame(position);
+            velocity.sub(primary.velocity).applyAxisAngle(primary.spinAxis, -this.frame.angle);
+            velocity.sub(this._tempVec3.copy(this.frame.spin).cross(position));
+            store.setPosition(i, position.x, position.y, position.z);
+            store.setVelocity(i, velocity.x, velocity.y, velocity.z);
+        }
+    }
+
+    // Frame angle and acceleration are set per evaluation; the angular
+    // velocity `spin` is the primary body's
+    frameState() {
+        const frame = this._frame || (this._frame = {
+            startAngle: 0,
+            angle: 0,
+            spin: new Vector3(),
+            acceleration: new Vector3()
+        });
+        frame.spin.copy(this.primaryBody.spinAxis).multiplyScalar(this.primaryBody.rotationSpeed);
+        return frame;
+    }
+
+    // Back to inertial coordinates at the end of a substep
+    leaveRotatingFrame(deltaTime) {
+        this.frame.angle = this.frame.startAngle + this.primaryBody.rotationSpeed * deltaTime;
+
+        const store = this.particles;
+        const position = this._tempVec1;
+        const velocity = this._tempVec2;
+        for (let i = 0; i < store.count; i++) {
+            store.readPosition(i, position);
+            store.readVelocity(i, velocity);
+            this.frameToInertial(position, velocity);
+            store.setPosition(i, position.x, position.y, position.z);
+            store.setVelocity(i, velocity.x, velocity.y, velocity.z);
+        }
+        this.frame = null;
+    }
+
+    // Inertial point to frame coordinates, in place
+    pointToFrame(position) {
+        const primary = this.primaryBody;
+        return position.sub(primary.position).applyAxisAngle(primary.spinAxis, -this.frame.angle);
+    }
+
+    // Frame position and velocity to inertial ones, in place
+    frameToInertial(position, velocity) {
+        const primary = this.primaryBody;
+        velocity.add(this._tempVec3.copy(this.frame.spin).cross(position))
+            .applyAxisAngle(primary.spinAxis, this.frame.angle).add(primary.velocity);
+        this.pointToInertial(position);
+    }
+
+    pointToInertial(position) {
+        const primary = this.primaryBody;
+        return position.applyAxisAngle(primary.spinAxis, this.frame.angle).add(primary.position);
+    }
+
+    // State blocks for the integrator: the particle arrays and, in N-body
+    // mode, the bodies packed into arrays of their own
+    getIntegrationState() {
+        if (this.parameters.orbitMode === 'nbody') {
+            return [this.particles, this.packBodyState()];
+        }
+        return [this.particles];
+    }
+
+    packBodyState() {
+        const count = this.bodies.length;
+        if (!this.bodyState || this.bodyState.count !== count) {
+            this.bodyState = {
+                count,
+                positions: new Float64Array(count * 3),
+                velocities: new Float64Array(count * 3),
+                accelerations: new Float64Array(count * 3)
+            };
+        }
+
+        const { positions, velocities } = this.bodyState;
+        this.bodies.forEach((body, i) => {
+            positions[i * 3] = body.position.x;
+            positions[i * 3 + 1] = body.position.y;
+            positions[i * 3 + 2] = body.position.z;
+            velocities[i * 3] = body.velocity.x;
+            velocities[i * 3 + 1] = body.velocity.y;
+            velocities[i * 3 + 2] = body.velocity.z;
+        });
+        return this.bodyState;
+    }
+
+    // Copies the integrator's body arrays back onto the bodies
+    unpackBodyState() {
+        const { positions, velocities } = this.bodyState;
+        this.bodies.forEach((body, i) => {
+            body.position.set(positions[i * 3], positions[i * 3 + 1], positions[i * 3 + 2]);
+            body.velocity.set(velocities[i * 3], velocities[i * 3 + 1], velocities[i * 3 + 2]);
+        });
+    }
+
+    // Fills accelerations for the current state. In Kepler mode bodies are
+    // placed where they will be `timeOffset` seconds into the step; in N-body
+    // mode the integrator moves them and we add the mutual body gravity.
+    computeAccelerations(timeOffset) {
+        if (this.frame) {
+            this.frame.angle = this.frame.startAngle + this.primaryBody.rotationSpeed * timeOffset;
+        }
+        if (this.parameters.selfGravity) {
+            this.buildSelfGravityTree();
+        }
+
+        if (this.parameters.orbitMode === 'nbody') {
+            this.unpackBodyState();
+            this.computeBodyAccelerations();
+            if (this.frame) {
+                // The frame falls with the primary body
+                this.frame.acceleration.copy(this.primaryBody.acceleration);
+            }
+            const { accelerations } = this.bodyState;
+            this.bodies.forEach((body, i) => {
+                accelerations[i * 3] = body.acceleration.x;
+                accelerations[i * 3 + 1] = body.acceleration.y;
+                accelerations[i * 3 + 2] = body.acceleration.z;
+            });
+        } else {
+            this.placeBodiesOnOrbits(timeOffset);
+            if (this.frame) {
+                // A fluid host on a prescribed orbit drags the frame with it
+                this.orbitalAcceleration(this.primaryBody, this.frame.acceleration);
+            }
+        }
+        this.updateTidalReference();
+
+        if (this.forcePool) {
+            // Same two passes, split across workers by region
+            this.forcePool.computeParticleAccelerations(this);
+            return;
+        }
+
+        // Optimize neighborhood calculation using spatial partitioning
+        const neighbors = this.findNeighbors();
+
+        // Kernel-weighted density summation and equation of state
+        this.computeDensityPressure(neighbors);
+
+        this.computeParticleAccelerations(neighbors);
+    }
+
+    // In Kepler mode the fluid host is held on its prescribed path, so a
+    // tidal body (a distant sun) must not pull the ocean off it: the fluid
+    // only feels the difference between the body's pull on a particle and
+    // its pull on the host's center. That host-center pull is collected
+    // here and subtracted from every particle. In N-body mode the host
+    // falls along with the fluid and full gravity applies.
+    updateTidalReference() {
+        this.computeTidalReference(this.tidalReference);
+    }
+
+    // The same pull for the current body positions, written to `out`
+    // without touching the state the next substep uses
+    computeTidalReference(out) {
+        out.set(0, 0, 0);
+        if (this.parameters.orbitMode === 'nbody') return out;
+
+        for (const body of this.bodies) {
+            if (!body.tidal || body === this.primaryBody) continue;
+            out.add(this.calculateGravitationalForce(
+                this.primaryBody.position, body.mass, body.position, body.gravityMultiplier));
+        }
+        return out;
+    }
+
+    // Gravity, SPH, tension, force field, surface drag and damping
+    // accelerations for the listed particles (all by default), plus the
+    // Coriolis and centrifugal terms in the rotating frame. Densities and
+    // pressures of these particles and their neighbors must be up to date.
+    computeParticleAccelerations(neighbors, owned = null, ownedCount = this.particles.count) {
+        const { starts, ends, indices, distances } = neighbors;
+        const forceContext = {
+            bodies: this.bodiesById,
+            primaryBody: this.primaryBody,
+            surfaceRadius: this.primaryBody.radius + this.parameters.fluidHeight,
+            neighbors
+        };
+
+        const store = this.particles;
+        const { positions, accelerations } = store;
+        const position = this._tempVec1;
+        const velocity = this._tempVec2;
+        const totalForce = this._totalForce || (this._totalForce = new Vector3());
+        const inertialPosition = this._inertialPosition || (this._inertialPosition = new Vector3());
+        const inertialVelocity = this._inertialVelocity || (this._inertialVelocity = new Vector3());
+        const frame = this.frame;
+        const primary = this.primaryBody;
+        const dragDepth = this.parameters.smoothingRadius;
+
+        for (let n = 0; n < ownedCount; n++) {
+            const i = owned ? owned[n] : n;
+            store.readPosition(i, position);
+            store.readVelocity(i, velocity);
+
+            // Body gravity and the scenario force fields live in inertial
+            // space; in the rotating frame they are evaluated there and the
+            // result turned into the frame
+            inertialPosition.copy(position);
+            inertialVelocity.copy(velocity);
+            if (frame) {
+                this.frameToInertial(inertialPosition, inertialVelocity);
+            }
+
+            totalForce.set(0, 0, 0);
+            for (const body of this.bodies) {
+                totalForce.add(this.calculateGravitationalForce(inertialPosition, body.mass, body.position, body.gravityMultiplier));
+            }
+            totalForce.sub(this.tidalReference);
+            applyForceFields(this.forceFields, inertialPosition, inertialVelocity, forceContext, totalForce);
+            this.addSurfacePressure(i, inertialPosition, totalForce);
+            if (frame) {
+                totalForce.sub(frame.acceleration).applyAxisAngle(primary.spinAxis, -frame.angle);
+            }
+
+            if (this.parameters.selfGravity) {
+                this.addSelfGravity(position, totalForce);
+            }
+
+            // Symmetric SPH pressure force
+            totalForce.add(this.calculatePressureForce(i, neighbors));
+
+            // Viscous diffusion of momentum between neighbors
+            totalForce.add(this.calculateViscosityForce(i, neighbors));
+            
+            // Add surface tension and cohesion from neighbors inside the tension radius
+            let tensionNeighborCount = 0;
+            const centerOfMass = this._tempVec3 || (this._tempVec3 = new Vector3());
+            centerOfMass.set(0, 0, 0);
+
+            for (let k = starts[i]; k < ends[i]; k++) {
+                const distance = distances[k];
+                if (distance >= this.parameters.surfaceTensionRadius) continue;
+                const j = indices[k];
+                const dx = positions[j * 3] - position.x;
+                const dy = positions[j * 3 + 1] - position.y;
+                const dz = positions[j * 3 + 2] - position.z;
+                tensionNeighborCount++;
+                centerOfMass.x += positions[j * 3];
+                centerOfMass.y += positions[j * 3 + 1];
+                centerOfMass.z += positions[j * 3 + 2];
+                
+                // Simplified tension forces
+                const direction = this._tempVec4 || (this._tempVec4 = new Vector3());
+                direction.set(dx, dy, dz).normalize();
+                direction.multiplyScalar((1 - distance / this.parameters.surfaceTensionRadius) * 
+                                      this.parameters.surfaceTensionStrength);
+                totalForce.add(direction);
+            }
+
+            if (tensionNeighborCount > 0) {
+                // Add cohesion force
+                centerOfMass.divideScalar(tensionNeighborCount)
+                           .sub(position)
+                           .multiplyScalar(this.parameters.cohesionStrength);
+                totalForce.add(centerOfMass);
+            }
+
+            // The bottom layer is dragged towards the velocity of the
+            // surface below it, which carries the planet's spin into the fluid
+            if (this.parameters.surfaceDrag > 0) {
+                const relative = this._tempVec4 || (this._tempVec4 = new Vector3());
+                relative.copy(inertialPosition).sub(primary.position);
+                if (relative.length() < primary.radius + dragDepth) {
+                    if (frame) {
+                        relative.copy(velocity);
+                    } else {
+                        relative.cross(this._tempVec3.copy(primary.spinAxis).multiplyScalar(primary.rotationSpeed))
+                            .add(velocity).sub(primary.velocity);
+                    }
+                    totalForce.addScaledVector(relative, -this.parameters.surfaceDrag);
+                }
+            }
+
+            // Damping, of the motion relative to the frame
+            totalForce.addScaledVector(velocity, -this.parameters.damping);
+
+            if (frame) {
+                // Coriolis, -2 spin × v, and centrifugal, -spin × (spin × x)
+                const spinCross = this._tempVec4 || (this._tempVec4 = new Vector3());
+                totalForce.addScaledVector(spinCross.copy(frame.spin).cross(velocity), -2);
+                spinCross.copy(frame.spin).cross(position);
+                totalForce.sub(this._tempVec3.copy(frame.spin).cross(spinCross));
+            }
+
+            accelerations[i * 3] = totalForce.x;
+            accelerations[i * 3 + 1] = totalForce.y;
+            accelerations[i * 3 + 2] = totalForce.z;
+        }
+    }
+
+    // Every body is a rigid, possibly moving and spinning, boundary
+    resolveCollisions() {
+        const { count } = this.particles;
+
+        for (let i = 0; i < count; i++) {
+            for (const body of this.bodies) {
+                if (this.resolveSurfaceContact(i, body)) break;
+            }
+        }
+    }
+
+    // Contact between particle i and a body's solid surface. A particle
+    // below the surface is projected back onto it; if it is moving into
+    // the surface (relative to the surface itself, which may be moving and
+    // spinning) the normal velocity is reflected with the restitution
+    // coefficient and the tangential velocity loses at most friction times
+    // the normal impulse. In N-body mode the body takes the opposite
+    // impulse, so grazing passes and splashes exchange momentum with the
+    // fluid. Returns whether the particle was in contact.
+    resolveSurfaceContact(i, body) {
+        const { positions, velocities } = this.particles;
+        const k = i * 3;
+        let nx = positions[k] - body.position.x;
+        let ny = positions[k + 1] - body.position.y;
+        let nz = positions[k + 2] - body.position.z;
+        const distance = Math.hypot(nx, ny, nz);
+        if (distance >= body.radius) return false;
+
+        if (distance > 1e-9) {
+            nx /= distance;
+            ny /= distance;
+            nz /= distance;
+        } else {
+            nx = body.spinAxis.x;
+            ny = body.spinAxis.y;
+            nz = body.spinAxis.z;
+        }
+        positions[k] = body.position.x + nx * body.radius;
+        positions[k + 1] = body.position.y + ny * body.radius;
+        positions[k + 2] = body.position.z + nz * body.radius;
+
+        // Velocity of the surface point: the body's motion plus its spin
+        const spin = body.spinAxis;
+        const rx = nx * body.radius;
+        const ry = ny * body.radius;
+        const rz = nz * body.radius;
+        const sx = body.velocity.x + body.rotationSpeed * (spin.y * rz - spin.z * ry);
+        const sy = body.velocity.y + body.rotationSpeed * (spin.z * rx - spin.x * rz);
+        const sz = body.velocity.z + body.rotationSpeed * (spin.x * ry - spin.y * rx);
+
+        const vx = velocities[k] - sx;
+        const vy = velocities[k + 1] - sy;
+        const vz = velocities[k + 2] - sz;
+        const normalSpeed = vx * nx + vy * ny + vz * nz;
+        if (normalSpeed >= 0) return true;
+
+        let tx = vx - normalSpeed * nx;
+        let ty = vy - normalSpeed * ny;
+        let tz = vz - normalSpeed * nz;
+        const tangentialSpeed = Math.hypot(tx, ty, tz);
+        const restitution = body.restitution ?? this.parameters.surfaceRestitution;
+        const friction = body.friction ?? this.parameters.surfaceFriction;
+        const normalImpulse = (1 + restitution) * -normalSpeed;
+        if (tangentialSpeed > 0) {
+            const keep = Math.max(0, 1 - friction * normalImpulse / tangentialSpeed);
+            tx *= keep;
+            ty *= keep;
+            tz *= keep;
+        }
+
+        const bounce = -restitution * normalSpeed;
+        const newX = sx + tx + bounce * nx;
+        const newY = sy + ty + bounce * ny;
+        const newZ = sz + tz + bounce * nz;
+
+        if (this.parameters.orbitMode === 'nbody' && body.mass > 0) {
+            const share = this.particleGravityMass() / body.mass;
+            body.velocity.x -= (newX - velocities[k]) * share;
+            body.velocity.y -= (newY - velocities[k + 1]) * share;
+            body.velocity.z -= (newZ - velocities[k + 2]) * share;
+        }
+
+        velocities[k] = newX;
+        velocities[k + 1] = newY;
+        velocities[k + 2] = newZ;
+        return true;
+    }
+
+    // Builds the spatial grid and the neighbor lists of every particle
+    // within the largest interaction radius in use, see SpatialHash.js
+    findNeighbors() {
+        const cutoff = Math.max(this.parameters.smoothingRadius, this.parameters.surfaceTensionRadius);
+        const { positions, count } = this.particles;
+        return this.neighborSearch.build(positions, count, cutoff).buildNeighborLists(cutoff);
+    }
+
+    computeDensityPressure(neighbors, owned = null, ownedCount = this.particles.count) {
+        const h = this.parameters.smoothingRadius;
+        const selfWeight = poly6(0, h);
+        const { starts, ends, distances } = neighbors;
+        const { densities, pressures } = this.particles;
+        const position = this._surfacePoint || (this._surfacePoint = new Vector3());
+
+        for (let n = 0; n < ownedCount; n++) {
+            const i = owned ? owned[n] : n;
+            let weight = selfWeight;
+
+            for (let k = starts[i]; k < ends[i]; k++) {
+                weight += poly6(distances[k] * distances[k], h);
+            }
+
+            this.particles.readPosition(i, position);
+            if (this.frame) {
+                this.pointToInertial(position);
+            }
+            densities[i] = this.particleMass * weight + this.parameters.density * this.surfaceWeight(position);
+
+            // Linear equation of state. Negative pressures are clamped so the
+            // free surface does not pull itself into clumps.
+            pressures[i] = Math.max(0, this.parameters.pressureStiffness *
+                                       (densities[i] - this.parameters.density));
+        }
+    }
+
+    calculatePressureForce(i, neighbors) {
+        const h = this.parameters.smoothingRadius;
+        const { positions, densities, pressures } = this.particles;
+        const { starts, ends, indices, distances } = neighbors;
+        const force = this._pressureForce || (this._pressureForce = new Vector3());
+        force.set(0, 0, 0);
+
+        const ownTerm = pressures[i] / (densities[i] * densities[i]);
+
+        for (let k = starts[i]; k < ends[i]; k++) {
+            const distance = distances[k];
+            if (distance >= h || distance === 0) continue;
+            const j = indices[k];
+
+            // -m (p_i/rho_i^2 + p_j/rho_j^2) grad W, pointing away from the neighbor
+            const sharedTerm = ownTerm + pressures[j] / (densities[j] * densities[j]);
+            const scale = this.particleMass * sharedTerm * spikyGradient(distance, h) / distance;
+            force.x += (positions[i * 3] - positions[j * 3]) * scale;
+            force.y += (positions[i * 3 + 1] - positions[j * 3 + 1]) * scale;
+            force.z += (positions[i * 3 + 2] - positions[j * 3 + 2]) * scale;
+        }
+
+        return force;
+    }
+
+    // Solid surfaces act as fluid at rest density filling the half-space
+    // beyond them. They add to the density of particles within a kernel
+    // radius, so the bottom of the ocean isn't short of neighbors and
+    // squeezed flat, and push those particles back out with a mirrored
+    // pressure term. Positions are inertial; surfaces are treated as flat
+    // across a kernel.
+    surfaceWeight(position) {
+        const h = this.parameters.smoothingRadius;
+        let weight = 0;
+        for (const body of this.bodies) {
+            const height = position.distanceTo(body.position) - body.radius;
+            if (height < h) {
+                weight += poly6HalfSpace(Math.max(0, height), h);
+            }
+        }
+        return weight;
+    }
+
+    // Adds the surfaces' pressure push on particle i at an inertial
+    // position to `out`
+    addSurfacePressure(i, position, out) {
+        const h = this.parameters.smoothingRadius;
+        const { densities, pressures } = this.particles;
+        if (pressures[i] === 0) return;
+
+        // -rho0 (p_i/rho_i^2 + p_i/rho_i^2) times the integrated gradient,
+        // along the outward normal
+        const scale = 2 * this.parameters.density * pressures[i] / (densities[i] * densities[i]);
+        const normal = this._surfaceNormal || (this._surfaceNormal = new Vector3());
+        for (const body of this.bodies) {
+            normal.copy(position).sub(body.position);
+            const distance = normal.length();
+            const height = distance - body.radius;
+            if (height >= h || distance < 1e-9) continue;
+            out.addScaledVector(normal, scale * spikyGradientHalfSpace(Math.max(0, height), h) / distance);
+        }
+    }
+
+    calculateViscosityForce(i, neighbors) {
+        const h = this.parameters.smoothingRadius;
+        const { velocities, densities } = this.particles;
+        const { starts, ends, indices, distances } = neighbors;
+        const force = this._viscosityForce || (this._viscosityForce = new Vector3());
+        force.set(0, 0, 0);
+
+        for (let k = starts[i]; k < ends[i]; k++) {
+            const distance = distances[k];
+            const j = indices[k];
+            if (distance >= h || densities[j] === 0) continue;
+
+            // nu * m / rho_j * (v_j - v_i) * laplacian W
+            const scale = this.parameters.viscosity * this.particleMass / densities[j] * viscosityLaplacian(distance, h);
+            force.x += (velocities[j * 3] - velocities[i * 3]) * scale;
+            force.y += (velocities[j * 3 + 1] - velocities[i * 3 + 1]) * scale;
+            force.z += (velocities[j * 3 + 2] - velocities[i * 3 + 2]) * scale;
+        }
+
+        return force;
+    }
+
+    // Chooses the particle mass so that a freshly seeded layout sits at the
+    // rest density, surfaces included. Only initializeParticles() calls
+    // this: later changes to the rest density or kernel radius act on the
+    // same particles.
+    calibrateParticleMass() {
+        this.particleMass = 1.0;
+        if (this.particles.count === 0) return;
+
+        const h = this.parameters.smoothingRadius;
+        const { starts, ends, distances } = this.findNeighbors();
+        const position = this._surfacePoint || (this._surfacePoint = new Vector3());
+        let totalWeight = 0;
+        let totalSurfaceWeight = 0;
+
+        for (let i = 0; i < this.particles.count; i++) {
+            let weight = poly6(0, h);
+            for (let k = starts[i]; k < ends[i]; k++) {
+                weight += poly6(distances[k] * distances[k], h);
+            }
+            totalWeight += weight;
+            // One surface supplies at most half of a particle's density, but
+            // overlapping bodies could add up to all of it and leave the
+            // fluid no mass at all; the fluid always keeps at least half
+            const surfaceShare = this.surfaceWeight(this.particles.readPosition(i, position));
+            totalSurfaceWeight += Math.min(0.5, surfaceShare);
+        }
+
+        this.particleMass = this.parameters.density * (this.particles.count - totalSurfaceWeight) / totalWeight;
+    }
+
+    calculateGravitationalForce(particlePos, bodyMass, bodyPos, multiplier = 1.0) {
+        const direction = this._gravityForce || (this._gravityForce = new Vector3());
+        direction.copy(bodyPos).sub(particlePos);
+        const distance = direction.length();
+        
+        // Adjusted minimum distance
+        const minDistance = 0.5;
+        const safeDist = Math.max(distance, minDistance);
+        
+        // Adjusted gravitational force scaling
+        const scaledG = this.scaledGravitationalConstant();
+        const forceMagnitude = (scaledG * bodyMass * multiplier) / (safeDist * safeDist);
+        
+        return direction.normalize().multiplyScalar(forceMagnitude);
+    }
+
+    setParameter(name, value) {
+        if (name in this.parameters) {
+            const previousRadius = this.primaryBody.radius;
+            this.parameters[name] = value;
+            
+            // Update relevant components based on parameter changes
+            if (name in BODY_PARAMETERS) {
+                this.applyBodyParameter(name, value);
+            }
+            if (name === 'orbitMode') {
+                this.initializeBodies();
+            }
+            else if (name === 'sunEnabled' && this.usesDefaultBodies) {
+                this.setBodies([]);
+            }
+            else if (name === 'planetRadius') {
+                // Instead of full reinitialization, adjust particle positions relative to new radius
+                const primary = this.primaryBody;
+                const store = this.particles;
+                const direction = this._tempVec1;
+                
+                for (let i = 0; i < store.count; i++) {
+                    // Calculate new position maintaining relative height above surface
+                    store.readPosition(i, direction).sub(primary.position);
+                    const height = Math.max(0, direction.length() - previousRadius);
+                    direction.normalize().multiplyScalar(primary.radius + height).add(primary.position);
+                    store.setPosition(i, direction.x, direction.y, direction.z);
+                    
+                    // Maintain current velocity direction but scale magnitude
+                    for (let k = i * 3; k < i * 3 + 3; k++) {
+                        store.velocities[k] *= 0.5; // Dampen velocities during radius change
+                    }
+                }
+            }
+            else if (name === 'particleCount' || name === 'fluidHeight') {
+                // Reinitialize particles with the new count or layer depth
+                this.initializeParticles();
+            }
+        }
+    }
+
+    // Helper method to generate random tangential vectors for particle distribution
+    getRandomTangentialVector(normal) {
+        // Create a random vector
+        const random = new Vector3(
+            this.random.next() - 0.5,
+            this.random.next() - 0.5,
+            this.random.next() - 0.5
+        );
+        
+        // Make it perpendicular to the normal
+        const tangent = random.clone()
+            .sub(normal.multiplyScalar(random.dot(normal)))
+            .normalize();
+        
+        return tangent;
+    }
+
+    resetToDefault() {
+        // Reset all parameters to defaults
+        Object.entries(this.defaultParameters).forEach(([key, value]) => {
+            this.setParameter(key, value);
+        });
+
+        // Back to the default planet and moon
+        this.setBodies([]);
+        this.forceFields = [];
+        this.setProbes([]);
+        
+        // Reinitialize particles with default configuration
+        this.initializeParticles();
+
+        // Restart the simulation clock
+        this.simulationTime = 0;
+        this.timeAccumulator = 0;
+        this.clampedSubsteps = 0;
+    }
+}
\ No newline at end of file
@@ -0,0 +1,75 @@
+// Runs a SimulationCore off the main thread.
+//
+// The page keeps a mirror core for structure (bodies, parameters, forces)
+// and forwards every state-changing call here as
+//   { type: 'call', method, args, generation }
+// Each { type: 'advance', frameDeltaSeconds, buffers } message steps the
+// core and answers with a 'frame' holding the new particle positions and
+// velocities, the body states and the tide gauge samples recorded since
+// the last frame. Particle arrays travel as transferables; the page sends
+// them back with the next 'advance' so they are reused.
+//
+// Where shared memory is available (a cross-origin isolated page) the
+// particle passes are further split across a ForcePool of workers.
+import { SimulationCore } from './SimulationCore.js';
+import { ForcePool } from './ForcePool.js';
+
+const core = new SimulationCore();
+const poolSize = ForcePool.defaultSize();
+const pool = poolSize > 0 ? new ForcePool(poolSize) : null;
+if (pool) {
+    core.setForcePool(pool);
+}
+let generation = 0;
+const spareBuffers = [];
+
+function takeArray(length, Type = Float32Array) {
+    const index = spareBuffers.findIndex(buffer => buffer.byteLength === length * Type.BYTES_PER_ELEMENT);
+    if (index >= 0) {
+        return new Type(spareBuffers.splice(index, 1)[0]);
+    }
+    return new Type(length);
+}
+
+function postFrame(steps, stepTime) {
+    const { count } = core.particles;
+    const positions = takeArray(count * 3);
+    const velocities = takeArray(count * 3, Float64Array);
+    positions.set(core.particles.positions);
+    velocities.set(core.particles.velocities);
+
+    const bodies = core.exportBodyState();
+    const probes = core.tideGauges.map(gauge => gauge.drain());
+
+    self.postMessage({
+        type: 'frame',
+        generation,
+        steps,
+        stepTime,
+        simulationTime: core.simulationTime,
+        effectiveTimeStep: core.effectiveTimeStep,
+        clampedSubsteps: core.clampedSubsteps,
+        positions,
+        velocities,
+        bodies,
+        probes
+    }, [positions.buffer, velocities.buffer, bodies.buffer]);
+}
+
+self.onmessage = ({ data }) => {
+    if (data.type === 'call') {
+        core[data.method](...data.args);
+        if (pool) {
+            pool.forward(data.method, data.args);
+        }
+        generation = data.generation;
+    } else if (data.type === 'advance') {
+        spareBuffers.push(...data.buffers);
+        const started = performance.now();
+        const steps = core.update(data.frameDeltaSeconds);
+        if (pool) {
+            pool.endSession();
+        }
+        postFrame(steps, steps > 0 ? (performance.now() - started) / steps : null);
+    }
+};
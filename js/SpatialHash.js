@@ -0,0 +1,188 @@
+// Uniform grid neighbor search over flat typed arrays.
+//
+// Cells are hashed from their integer coordinates into a power-of-two table
+// and particles are counting-sorted by bucket, so a rebuild is two linear
+// passes with no allocation once the arrays have grown to size. Neighbor
+// lists are stored compressed: the neighbors of particle i are
+//   indices[starts[i]] .. indices[ends[i] - 1]
+// with the matching distances in `distances`.
+//
+// Positions are read from a flat [x0, y0, z0, x1, ...] array. The grid can
+// be built over a subset of the particles (a worker's region plus its halo)
+// and lists filled for a subset too. The table size only depends on the
+// total particle count and buckets keep ascending particle order, so a
+// particle's neighbors come out in the same order whatever subset it was
+// found in.
+
+const PRIME_X = 73856093;
+const PRIME_Y = 19349663;
+const PRIME_Z = 83492791;
+const MAX_STAMP = 0x7fffffff;
+
+function nextPowerOfTwo(value) {
+    let size = 1;
+    while (size < value) size *= 2;
+    return size;
+}
+
+export class SpatialHash {
+    constructor() {
+        this.cellSize = 1;
+        this.count = 0;
+        this.positions = null;
+
+        this.tableMask = 0;
+        this.bucketStart = new Int32Array(1);  // First sorted slot of each bucket, plus an end marker
+        this.sorted = new Int32Array(0);       // Particle indices ordered by bucket
+        this.bucketOf = new Int32Array(0);     // Bucket of each hashed particle
+
+        this.starts = new Int32Array(0);
+        this.ends = new Int32Array(0);
+        this.indices = new Int32Array(0);
+        this.distances = new Float64Array(0);
+
+        // Stamp of the last query that scanned each bucket, so two nearby
+        // cells hashing to the same bucket aren't scanned twice
+        this.bucketStamp = new Int32Array(0);
+        this.queryStamp = 0;
+    }
+
+    hashCell(ix, iy, iz) {
+        return (Math.imul(ix, PRIME_X) ^ Math.imul(iy, PRIME_Y) ^ Math.imul(iz, PRIME_Z)) & this.tableMask;
+    }
+
+    // Sorts particles into cells of the given size. `count` is the total
+    // number of particles; `members` optionally lists (in ascending order)
+    // the `memberCount` particles to hash, by default all of them.
+    build(positions, count, cellSize, members = null, memberCount = count) {
+        this.positions = positions;
+        this.count = count;
+        this.cellSize = cellSize;
+
+        const tableSize = nextPowerOfTwo(Math.max(2 * count, 64));
+        if (this.bucketStart.length !== tableSize + 1) {
+            this.bucketStart = new Int32Array(tableSize + 1);
+            this.bucketStamp = new Int32Array(tableSize);
+            this.queryStamp = 0;
+        } else {
+            this.bucketStart.fill(0);
+        }
+        this.tableMask = tableSize - 1;
+        if (this.sorted.length < memberCount) {
+            this.sorted = new Int32Array(memberCount);
+            this.bucketOf = new Int32Array(memberCount);
+        }
+
+        // Count particles per bucket...
+        const inverseCell = 1 / cellSize;
+        for (let m = 0; m < memberCount; m++) {
+            const i = members ? members[m] : m;
+            const bucket = this.hashCell(
+                Math.floor(positions[i * 3] * inverseCell),
+                Math.floor(positions[i * 3 + 1] * inverseCell),
+                Math.floor(positions[i * 3 + 2] * inverseCell));
+            this.bucketOf[m] = bucket;
+            this.bucketStart[bucket + 1]++;
+        }
+
+        // ...turn the counts into start offsets...
+        for (let b = 0; b < tableSize; b++) {
+            this.bucketStart[b + 1] += this.bucketStart[b];
+        }
+
+        // ...and scatter, walking backwards so each bucket keeps index order
+        for (let m = memberCount - 1; m >= 0; m--) {
+            const bucket = this.bucketOf[m];
+            this.sorted[--this.bucketStart[bucket + 1]] = members ? members[m] : m;
+        }
+        // Scattering moved each bucket's end marker back to its start, one
+        // slot to the right of where it belongs
+        this.bucketStart.copyWithin(0, 1);
+        this.bucketStart[tableSize] = memberCount;
+
+        return this;
+    }
+
+    // Calls callback(j, distance) for every hashed particle within `radius`
+    // of the point, including one sitting exactly on it
+    forEachInRadius(x, y, z, radius, callback) {
+        const positions = this.positions;
+        const inverseCell = 1 / this.cellSize;
+        const reach = Math.ceil(radius * inverseCell);
+        const cx = Math.floor(x * inverseCell);
+        const cy = Math.floor(y * inverseCell);
+        const cz = Math.floor(z * inverseCell);
+        const radiusSq = radius * radius;
+        if (this.queryStamp === MAX_STAMP) {
+            // Stamps live in an Int32Array; start over before they overflow
+            this.bucketStamp.fill(0);
+            this.queryStamp = 0;
+        }
+        const stamp = ++this.queryStamp;
+
+        for (let dx = -reach; dx <= reach; dx++) {
+            for (let dy = -reach; dy <= reach; dy++) {
+                for (let dz = -reach; dz <= reach; dz++) {
+                    const bucket = this.hashCell(cx + dx, cy + dy, cz + dz);
+                    if (this.bucketStamp[bucket] === stamp) continue;
+                    this.bucketStamp[bucket] = stamp;
+
+                    const end = this.bucketStart[bucket + 1];
+                    for (let s = this.bucketStart[bucket]; s < end; s++) {
+                        const j = this.sorted[s];
+                        const ox = positions[j * 3] - x;
+                        const oy = positions[j * 3 + 1] - y;
+                        const oz = positions[j * 3 + 2] - z;
+                        const distanceSq = ox * ox + oy * oy + oz * oz;
+                        if (distanceSq < radiusSq) {
+                            callback(j, Math.sqrt(distanceSq));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // Fills the compressed neighbor lists, excluding the particle itself,
+    // for all pairs closer than `cutoff`. `queries` optionally lists the
+    // `queryCount` particles that need lists, by default all of them.
+    buildNeighborLists(cutoff, queries = null, queryCount = this.count) {
+        if (this.starts.length < this.count) {
+            this.starts = new Int32Array(this.count);
+            this.ends = new Int32Array(this.count);
+        }
+
+        let total = 0;
+        let current = 0;
+        const collect = (j, distance) => {
+            if (j === current) return;
+            if (total === this.indices.length) {
+                this.grow();
+            }
+            this.indices[total] = j;
+            this.distances[total] = distance;
+            total++;
+        };
+
+        const positions = this.positions;
+        for (let q = 0; q < queryCount; q++) {
+            const i = queries ? queries[q] : q;
+            this.starts[i] = total;
+            current = i;
+            this.forEachInRadius(positions[i * 3], positions[i * 3 + 1], positions[i * 3 + 2], cutoff, collect);
+            this.ends[i] = total;
+        }
+
+        return this;
+    }
+
+    grow() {
+        const capacity = Math.max(1024, this.indices.length * 2);
+        const indices = new Int32Array(capacity);
+        const distances = new Float64Array(capacity);
+        indices.set(this.indices);
+        distances.set(this.distances);
+        this.indices = indices;
+        this.distances = distances;
+    }
+}
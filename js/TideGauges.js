@@ -0,0 +1,132 @@
+// Tide gauges: named probes fixed to the surface of the fluid host that
+// record the local fluid height after every physics step.
+//
+// Scenarios list them as
+//
+//   probes: [{ name: 'harbor', latitude: 30, longitude: -45, radius: 1 }]
+//
+// Latitude and longitude are in degrees, measured from the body's spin
+// equator and turning with the body. `radius` is how far along the surface
+// a particle may be from the gauge to count as local; it defaults to the
+// smoothing radius.
+const DEGREES = Math.PI / 180;
+
+export class TideGauge {
+    constructor(definition, maxSamples = Infinity) {
+        this.name = String(definition.name);
+        this.latitude = definition.latitude;
+        this.longitude = definition.longitude;
+        this.radius = definition.radius ?? null;
+
+        // Recorded series, one entry per physics step. Beyond maxSamples the
+        // oldest entries are dropped; every gauge records the same steps
+        // under the same limit, so the series stay aligned.
+        this.maxSamples = maxSamples;
+        this.times = [];
+        this.heights = [];
+    }
+
+    toDefinition() {
+        const definition = { name: this.name, latitude: this.latitude, longitude: this.longitude };
+        if (this.radius !== null) {
+            definition.radius = this.radius;
+        }
+        return definition;
+    }
+
+    // Height of the fluid above the surface at the gauge: the mean height
+    // of the particles within `radius` of it, measured along the surface,
+    // or 0 when the gauge is dry. That is the local counterpart of the
+    // mean sea level in Diagnostics.js. The column is gathered from `grid` (a SpatialHash
+    // over the current positions) one slab at a time from the surface up,
+    // stopping at the first empty slab, so spray that has come loose
+    // doesn't count.
+    measure(core, grid, direction) {
+        const body = core.primaryBody;
+        const positions = core.particles.positions;
+        direction = body.surfaceDirection(this.latitude * DEGREES, this.longitude * DEGREES, direction);
+
+        const reach = this.radius ?? core.parameters.smoothingRadius;
+        const cosLimit = Math.cos(Math.min(Math.PI, reach / body.radius));
+        const center = body.position;
+        let heightSum = 0;
+        let columnCount = 0;
+
+        for (let slab = 0; ; slab++) {
+            const bottom = slab * reach;
+            const top = bottom + reach;
+            const middle = body.radius + bottom + 0.5 * reach;
+            // Covers the slab's part of the column, which widens with height
+            const queryRadius = reach * (body.radius + top) / body.radius + 0.5 * reach;
+            let slabCount = 0;
+
+            grid.forEachInRadius(center.x + direction.x * middle, center.y + direction.y * middle,
+                center.z + direction.z * middle, queryRadius, (j) => {
+                    const dx = positions[j * 3] - center.x;
+                    const dy = positions[j * 3 + 1] - center.y;
+                    const dz = positions[j * 3 + 2] - center.z;
+                    const distance = Math.sqrt(dx * dx + dy * dy + dz * dz);
+                    if (dx * direction.x + dy * direction.y + dz * direction.z < distance * cosLimit) return;
+                    const height = distance - body.radius;
+                    if (height >= top || (slab > 0 && height < bottom)) return;
+                    heightSum += height;
+                    slabCount++;
+                });
+
+            if (slabCount === 0) break;
+            columnCount += slabCount;
+        }
+        return columnCount > 0 ? heightSum / columnCount : 0;
+    }
+
+    record(time, height) {
+        this.times.push(time);
+        this.heights.push(height);
+        this.trim();
+    }
+
+    // Hands over the samples recorded since the last drain
+    drain() {
+        const samples = { times: this.times, heights: this.heights };
+        this.times = [];
+        this.heights = [];
+        return samples;
+    }
+
+    append(samples) {
+        this.times.push(...samples.times);
+        this.heights.push(...samples.heights);
+        this.trim();
+    }
+
+    trim() {
+        const excess = this.times.length - this.maxSamples;
+        if (excess > 0) {
+            this.times.splice(0, excess);
+            this.heights.splice(0, excess);
+        }
+    }
+
+    clear() {
+        this.times = [];
+        this.heights = [];
+    }
+}
+
+// Quotes a CSV field when it would otherwise break the row
+function csvField(text) {
+    return /[",\r\n]/.test(text) ? `"${text.replace(/"/g, '""')}"` : text;
+}
+
+// Every gauge's series as CSV with a time column and one height column per
+// gauge. Gauges record on the same steps, so rows line up by index.
+export function tideGaugeCsv(gauges) {
+    const header = ['time', ...gauges.map(gauge => csvField(gauge.name))];
+    const lines = [header.join(',')];
+    const rows = Math.max(0, ...gauges.map(gauge => gauge.times.length));
+    for (let row = 0; row < rows; row++) {
+        const time = gauges.find(gauge => row < gauge.times.length).times[row];
+        lines.push([time, ...gauges.map(gauge => gauge.heights[row] ?? '')].join(','));
+    }
+    return lines.join('\n') + '\n';
+}
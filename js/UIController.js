@@ -1,9 +1,17 @@
+import { integrators } from './Integrators.js';
+import { forceFieldTypes } from './ForceFields.js';
+import { tideGaugeCsv } from './TideGauges.js';
+import { DiagnosticsCharts, downloadCsv } from './DiagnosticsCharts.js';
+
 export class UIController {
     constructor(app) {
         this.app = app;
         this.isPlaying = false;  // Start paused
         this.setupEventListeners();
         this.createControls();
+        this.createForceControls();
+        this.createProbeControls();
+        this.createChartControls();
         this.debounceTimeout = null;
     }
 
@@ -62,6 +70,44 @@ export class UIController {
                     default: 0.0, 
                     label: 'Planet Rotation',
                     tooltip: 'Rotation speed of the planet. Positive values rotate with orbit direction, negative values rotate against it.'
+                },
+                { 
+                    name: 'planetAxialTilt', 
+                    min: 0, 
+                    max: 3.14, 
+                    step: 0.01, 
+                    default: 0.0, 
+                    label: 'Axial Tilt (rad)',
+                    tooltip: 'Obliquity of the planet: angle between its spin axis and the orbit\'s reference pole. A tilted planet carries points through the bulge at different latitudes, giving unequal daily high tides.'
+                },
+                { 
+                    name: 'planetTiltAzimuth', 
+                    min: 0, 
+                    max: 6.28, 
+                    step: 0.01, 
+                    default: 0.0, 
+                    label: 'Tilt Direction (rad)',
+                    tooltip: 'Direction the spin axis leans towards, measured in the orbital reference plane.'
+                },
+                { 
+                    name: 'surfaceDrag', 
+                    min: 0, 
+                    max: 5.0, 
+                    step: 0.05, 
+                    default: 0.2, 
+                    label: 'Surface Drag',
+                    tooltip: 'How quickly the spinning surface drags the fluid right above it along (per simulated second). This is what sets the ocean turning with the planet.'
+                },
+                { 
+                    name: 'rotatingFrame', 
+                    type: 'select', 
+                    options: [
+                        { value: false, label: 'Inertial' },
+                        { value: true, label: 'Co-rotating' }
+                    ], 
+                    default: false, 
+                    label: 'Fluid Frame',
+                    tooltip: 'Frame the fluid is solved in. Co-rotating follows the planet\'s spin and adds explicit Coriolis and centrifugal forces, and damping then slows motion relative to the planet rather than absolute motion.'
                 }
             ],
             moon: [
@@ -90,7 +136,43 @@ export class UIController {
                     step: 0.5, 
                     default: 15.0, 
                     label: 'Orbit Radius',
-                    tooltip: 'Distance between moon and planet. Larger values place the moon further away (weaker influence), smaller values bring it closer (stronger influence).'
+                    tooltip: 'Semi-major axis of the moon\'s orbit (its average distance from the planet). Larger values place the moon further away (weaker influence), smaller values bring it closer (stronger influence).'
+                },
+                { 
+                    name: 'moonEccentricity', 
+                    min: 0, 
+                    max: 0.9, 
+                    step: 0.01, 
+                    default: 0.0, 
+                    label: 'Eccentricity',
+                    tooltip: 'Shape of the orbit. 0 is a circle; higher values stretch it into an ellipse so the moon swings close at periapsis and far at apoapsis, modulating the tides.'
+                },
+                { 
+                    name: 'moonInclination', 
+                    min: 0, 
+                    max: 3.14, 
+                    step: 0.01, 
+                    default: 0.0, 
+                    label: 'Inclination (rad)',
+                    tooltip: 'Tilt of the orbital plane against the planet\'s equator. 0 keeps the moon in the equatorial plane.'
+                },
+                { 
+                    name: 'moonArgumentOfPeriapsis', 
+                    min: 0, 
+                    max: 6.28, 
+                    step: 0.01, 
+                    default: 0.0, 
+                    label: 'Arg. of Periapsis (rad)',
+                    tooltip: 'Angle within the orbital plane from the ascending node to the closest approach point.'
+                },
+                { 
+                    name: 'moonAscendingNode', 
+                    min: 0, 
+                    max: 6.28, 
+                    step: 0.01, 
+                    default: 0.0, 
+                    label: 'Ascending Node (rad)',
+                    tooltip: 'Direction in which the orbit crosses the equatorial plane going upwards.'
                 },
                 { 
                     name: 'moonOrbitalSpeed', 
@@ -99,7 +181,18 @@ export class UIController {
                     step: 0.1, 
                     default: 0.5, 
                     label: 'Orbital Speed',
-                    tooltip: 'Speed of moon\'s orbit. Higher values make the moon orbit faster, lower values create slower orbits.'
+                    tooltip: 'Mean motion of the moon in radians per second. Higher values make the moon orbit faster, lower values create slower orbits.'
+                },
+                { 
+                    name: 'orbitMode', 
+                    type: 'select', 
+                    options: [
+                        { value: 'kepler', label: 'Prescribed (Kepler)' },
+                        { value: 'nbody', label: 'N-body' }
+                    ], 
+                    default: 'kepler', 
+                    label: 'Orbit Mode',
+                    tooltip: 'Prescribed keeps the moon on its Keplerian orbit regardless of masses. N-body integrates planet and moon under mutual gravity around their barycenter, so masses change the orbit and the planet wobbles.'
                 },
                 { 
                     name: 'moonRotationSpeed', 
@@ -109,13 +202,82 @@ export class UIController {
                     default: 0.0, 
                     label: 'Moon Rotation',
                     tooltip: 'Rotation speed of the moon. Positive values rotate with orbit direction, negative values rotate against it.'
+                },
+                { 
+                    name: 'moonAxialTilt', 
+                    min: 0, 
+                    max: 3.14, 
+                    step: 0.01, 
+                    default: 0.0, 
+                    label: 'Moon Axial Tilt (rad)',
+                    tooltip: 'Tilt of the moon\'s spin axis.'
+                }
+            ],
+            sun: [
+                { 
+                    name: 'sunEnabled', 
+                    type: 'select', 
+                    options: [
+                        { value: false, label: 'Off' },
+                        { value: true, label: 'On' }
+                    ], 
+                    default: false, 
+                    label: 'Sun',
+                    tooltip: 'Adds a distant sun whose tide combines with the moon\'s: spring tides when they line up, neap tides when they are at right angles.'
+                },
+                { 
+                    name: 'sunMass', 
+                    min: 1e5, 
+                    max: 1e8, 
+                    step: 1e5, 
+                    default: 4.4e6, 
+                    label: 'Sun Mass',
+                    tooltip: 'Mass of the sun. The solar tide grows with mass over distance cubed; the default gives about half the lunar tide, as on Earth.'
+                },
+                { 
+                    name: 'sunDistance', 
+                    min: 50, 
+                    max: 2000, 
+                    step: 10, 
+                    default: 400.0, 
+                    label: 'Sun Distance',
+                    tooltip: 'Distance from the planet to the sun.'
+                },
+                { 
+                    name: 'sunPeriod', 
+                    min: 5, 
+                    max: 1000, 
+                    step: 1, 
+                    default: 60.0, 
+                    label: 'Apparent Period (s)',
+                    tooltip: 'Time for the sun to go once around the sky as seen from the planet. Together with the moon\'s period it sets the length of the spring-neap cycle.'
+                },
+                { 
+                    name: 'sunInitialAngle', 
+                    min: 0, 
+                    max: 6.28, 
+                    step: 0.01, 
+                    default: 0, 
+                    label: 'Initial Angle (rad)',
+                    tooltip: 'Position of the sun along its apparent orbit at the start.'
+                },
+                { 
+                    name: 'sunLight', 
+                    type: 'select', 
+                    options: [
+                        { value: true, label: 'Sun' },
+                        { value: false, label: 'Fixed' }
+                    ], 
+                    default: true, 
+                    label: 'Key Light',
+                    tooltip: 'Light the scene from the sun\'s direction when there is one, so day and night follow it.'
                 }
             ],
             fluid: [
                 { 
                     name: 'particleCount', 
                     min: 100, 
-                    max: 10000, 
+                    max: 100000, 
                     step: 100, 
                     default: 1000, 
                     label: 'Particle Count',
@@ -139,6 +301,33 @@ export class UIController {
                     label: 'Fluid Coverage',
                     tooltip: 'How much of planet surface is covered by fluid. Higher values cover more surface area, lower values concentrate fluid more.'
                 },
+                { 
+                    name: 'density', 
+                    min: 100, 
+                    max: 5000, 
+                    step: 10, 
+                    default: 1000.0, 
+                    label: 'Rest Density',
+                    tooltip: 'Density the fluid settles at. The pressure solve pushes particles apart when they are packed above this density.'
+                },
+                { 
+                    name: 'pressureStiffness', 
+                    min: 1, 
+                    max: 2000, 
+                    step: 1, 
+                    default: 200.0, 
+                    label: 'Pressure Stiffness',
+                    tooltip: 'How strongly the fluid resists compression. Higher values keep the ocean incompressible but need smaller time steps.'
+                },
+                { 
+                    name: 'smoothingRadius', 
+                    min: 0.2, 
+                    max: 3.0, 
+                    step: 0.1, 
+                    default: 1.0, 
+                    label: 'Smoothing Radius',
+                    tooltip: 'Interaction radius of the SPH kernel. Larger values smooth the fluid over more neighbors at higher cost.'
+                },
                 { 
                     name: 'viscosity', 
                     min: 0.1, 
@@ -146,7 +335,63 @@ export class UIController {
                     step: 0.1, 
                     default: 1.0, 
                     label: 'Fluid Viscosity',
-                    tooltip: 'Thickness of the fluid. Higher values make fluid more honey-like, lower values make it more water-like.'
+                    tooltip: 'Thickness of the fluid (kinematic viscosity). Higher values make fluid more honey-like, lower values make it more water-like.'
+                },
+                { 
+                    name: 'surfaceRestitution', 
+                    min: 0, 
+                    max: 1, 
+                    step: 0.05, 
+                    default: 0.1, 
+                    label: 'Surface Restitution',
+                    tooltip: 'How much of its impact speed a particle keeps when it hits a planet or moon surface. 0 stops it dead along the normal, 1 bounces it back fully.'
+                },
+                { 
+                    name: 'surfaceFriction', 
+                    min: 0, 
+                    max: 2, 
+                    step: 0.05, 
+                    default: 0.3, 
+                    label: 'Surface Friction',
+                    tooltip: 'Friction coefficient between the fluid and solid body surfaces. Higher values slow particles sliding along the ground more on each contact.'
+                },
+                { 
+                    name: 'selfGravity', 
+                    type: 'select', 
+                    options: [
+                        { value: false, label: 'Off' },
+                        { value: true, label: 'On' }
+                    ], 
+                    default: false, 
+                    label: 'Self-Gravity',
+                    tooltip: 'Lets fluid particles attract each other through a Barnes-Hut tree. Matters for massive oceans, rings and disrupted moons; in N-body mode the fluid also pulls on the bodies.'
+                },
+                { 
+                    name: 'fluidMass', 
+                    min: 0, 
+                    max: 2000, 
+                    step: 10, 
+                    default: 100.0, 
+                    label: 'Fluid Mass',
+                    tooltip: 'Mass of the whole fluid, in the same units as the planet and moon masses. Sets how strongly the fluid attracts with self-gravity and how much momentum it exchanges with bodies it hits in N-body mode.'
+                },
+                { 
+                    name: 'selfGravityOpeningAngle', 
+                    min: 0, 
+                    max: 1.5, 
+                    step: 0.05, 
+                    default: 0.5, 
+                    label: 'Opening Angle',
+                    tooltip: 'Accuracy of the self-gravity tree. Groups of particles that look smaller than this angle are treated as one mass; 0 sums every pair exactly but is slow.'
+                },
+                { 
+                    name: 'selfGravitySoftening', 
+                    min: 0.01, 
+                    max: 2.0, 
+                    step: 0.01, 
+                    default: 0.3, 
+                    label: 'Softening Length',
+                    tooltip: 'Distance below which particle gravity stops growing, so close pairs do not fling each other apart.'
                 }
             ],
             simulation: [
@@ -157,7 +402,60 @@ export class UIController {
                     step: 0.1, 
                     default: 1.0, 
                     label: 'Time Scale',
-                    tooltip: 'Speed of simulation. Higher values make simulation run faster, lower values slow it down.'
+                    tooltip: 'Simulated seconds per wall-clock second. Higher values make simulation run faster, lower values slow it down.'
+                },
+                { 
+                    name: 'fixedTimeStep', 
+                    min: 0.001, 
+                    max: 0.05, 
+                    step: 0.001, 
+                    default: 1 / 120, 
+                    label: 'Physics Step',
+                    tooltip: 'Size of one physics step in simulated seconds. Smaller steps are more accurate and stable but cost more per frame.'
+                },
+                { 
+                    name: 'maxSubsteps', 
+                    min: 1, 
+                    max: 32, 
+                    step: 1, 
+                    default: 8, 
+                    label: 'Max Substeps',
+                    tooltip: 'Maximum physics steps per rendered frame. If the simulation cannot keep up, it slows down instead of skipping ahead.'
+                },
+                { 
+                    name: 'courantFactor', 
+                    min: 0.05, 
+                    max: 1.0, 
+                    step: 0.05, 
+                    default: 0.4, 
+                    label: 'Courant Factor',
+                    tooltip: 'Safety factor for the adaptive time step. Each physics step is split so no particle moves or accelerates too far within one kernel radius; lower values are safer but slower.'
+                },
+                { 
+                    name: 'maxAdaptiveSubsteps', 
+                    min: 1, 
+                    max: 1024, 
+                    step: 1, 
+                    default: 64, 
+                    label: 'Max Adaptive Substeps',
+                    tooltip: 'Most pieces one physics step may be split into. When the stability limit asks for more, the step runs anyway and the status line counts it as unstable.'
+                },
+                { 
+                    name: 'integrator', 
+                    type: 'select', 
+                    options: Object.values(integrators).map(({ name, label }) => ({ value: name, label })), 
+                    default: 'leapfrog', 
+                    label: 'Integrator',
+                    tooltip: 'Time integration scheme. Symplectic Euler is cheapest, Verlet and leapfrog conserve energy better, RK4 is most accurate per step but costs four force evaluations.'
+                },
+                { 
+                    name: 'damping', 
+                    min: 0, 
+                    max: 20.0, 
+                    step: 0.1, 
+                    default: 0.3, 
+                    label: 'Damping',
+                    tooltip: 'Global velocity damping rate (per simulated second) applied to every particle. Unlike viscosity it also slows fluid moving as a whole; set to 0 for undamped motion.'
                 },
                 { 
                     name: 'gravitationalConstant', 
@@ -185,6 +483,13 @@ export class UIController {
                 const label = document.createElement('label');
                 label.textContent = control.label;
                 label.title = control.tooltip; // Add tooltip to label
+
+                if (control.type === 'select') {
+                    container.appendChild(label);
+                    container.appendChild(this.createSelectControl(control));
+                    groupDiv.appendChild(container);
+                    return;
+                }
                 
                 const input = document.createElement('input');
                 input.type = 'number';
@@ -228,13 +533,269 @@ export class UIController {
         });
     }
 
+    createForceControls() {
+        const forceControls = document.getElementById('force-controls');
+        if (!forceControls) return;
+
+        const groupDiv = document.createElement('div');
+        groupDiv.className = 'control-group';
+        const groupTitle = document.createElement('h4');
+        groupTitle.textContent = 'Force Fields';
+        groupDiv.appendChild(groupTitle);
+
+        // Row for adding a new field of the chosen type
+        const addRow = document.createElement('div');
+        addRow.className = 'control-item';
+        const typeSelect = document.createElement('select');
+        typeSelect.className = 'select-input';
+        Object.entries(forceFieldTypes).forEach(([type, { label }]) => {
+            const option = document.createElement('option');
+            option.value = type;
+            option.textContent = label;
+            typeSelect.appendChild(option);
+        });
+        const addButton = document.createElement('button');
+        addButton.textContent = 'Add';
+        addButton.addEventListener('click', () => {
+            this.app.fluidSimulator.addForce({ type: typeSelect.value });
+            this.renderForceList();
+        });
+        addRow.appendChild(typeSelect);
+        addRow.appendChild(addButton);
+        groupDiv.appendChild(addRow);
+
+        this.forceList = document.createElement('div');
+        groupDiv.appendChild(this.forceList);
+        forceControls.appendChild(groupDiv);
+
+        this.renderForceList();
+    }
+
+    // Rebuilds the editor for the simulator's current force fields
+    renderForceList() {
+        if (!this.forceList) return;
+        this.forceList.innerHTML = '';
+
+        this.app.fluidSimulator.getForces().forEach((force, index) => {
+            const type = forceFieldTypes[force.type];
+            const forceDiv = document.createElement('div');
+            forceDiv.className = 'force-item';
+
+            const header = document.createElement('div');
+            header.className = 'control-item';
+            const enabled = document.createElement('input');
+            enabled.type = 'checkbox';
+            enabled.checked = force.enabled;
+            enabled.title = 'Enable or disable this force field';
+            enabled.addEventListener('change', (e) => {
+                this.app.fluidSimulator.setForceValue(index, 'enabled', e.target.checked);
+            });
+            const title = document.createElement('label');
+            title.textContent = type.label;
+            const removeButton = document.createElement('button');
+            removeButton.textContent = 'Remove';
+            removeButton.addEventListener('click', () => {
+                this.app.fluidSimulator.removeForce(index);
+                this.renderForceList();
+            });
+            header.appendChild(enabled);
+            header.appendChild(title);
+            header.appendChild(removeButton);
+            forceDiv.appendChild(header);
+
+            type.fields.forEach(field => {
+                const row = document.createElement('div');
+                row.className = 'control-item';
+                const label = document.createElement('label');
+                label.textContent = field.name;
+                row.appendChild(label);
+
+                if (field.type === 'vector') {
+                    const vector = [...force[field.name]];
+                    vector.forEach((component, axis) => {
+                        const input = document.createElement('input');
+                        input.type = 'number';
+                        input.step = 0.1;
+                        input.value = component;
+                        input.className = 'vector-input';
+                        input.addEventListener('change', (e) => {
+                            vector[axis] = parseFloat(e.target.value) || 0;
+                            this.app.fluidSimulator.setForceValue(index, field.name, [...vector]);
+                        });
+                        row.appendChild(input);
+                    });
+                } else {
+                    const input = document.createElement('input');
+                    input.type = field.type === 'number' ? 'number' : 'text';
+                    input.step = 0.1;
+                    input.value = force[field.name];
+                    input.className = 'number-input';
+                    input.addEventListener('change', (e) => {
+                        const value = field.type === 'number' ? (parseFloat(e.target.value) || 0) : e.target.value;
+                        this.app.fluidSimulator.setForceValue(index, field.name, value);
+                    });
+                    row.appendChild(input);
+                }
+
+                forceDiv.appendChild(row);
+            });
+
+            this.forceList.appendChild(forceDiv);
+        });
+    }
+
+    createProbeControls() {
+        const probeControls = document.getElementById('probe-controls');
+        if (!probeControls) return;
+
+        const groupDiv = document.createElement('div');
+        groupDiv.className = 'control-group';
+        const groupTitle = document.createElement('h4');
+        groupTitle.textContent = 'Tide Gauges';
+        groupDiv.appendChild(groupTitle);
+
+        // Row for placing a new gauge at a latitude and longitude in degrees
+        const addRow = document.createElement('div');
+        addRow.className = 'control-item';
+        const nameInput = document.createElement('input');
+        nameInput.type = 'text';
+        nameInput.placeholder = 'Name';
+        nameInput.className = 'number-input';
+        const [latitudeInput, longitudeInput] = ['Lat', 'Lon'].map(placeholder => {
+            const input = document.createElement('input');
+            input.type = 'number';
+            input.step = 1;
+            input.placeholder = placeholder;
+            input.className = 'vector-input';
+            return input;
+        });
+        const addButton = document.createElement('button');
+        addButton.textContent = 'Add';
+        addButton.addEventListener('click', () => {
+            const probes = this.app.fluidSimulator.getProbes();
+            probes.push({
+                name: nameInput.value || `gauge ${probes.length + 1}`,
+                latitude: parseFloat(latitudeInput.value) || 0,
+                longitude: parseFloat(longitudeInput.value) || 0
+            });
+            this.app.fluidSimulator.setProbes(probes);
+            nameInput.value = '';
+            this.renderProbeList();
+        });
+        addRow.appendChild(nameInput);
+        addRow.appendChild(latitudeInput);
+        addRow.appendChild(longitudeInput);
+        addRow.appendChild(addButton);
+        groupDiv.appendChild(addRow);
+
+        this.probeList = document.createElement('div');
+        groupDiv.appendChild(this.probeList);
+
+        const exportButton = document.createElement('button');
+        exportButton.textContent = 'Export CSV';
+        exportButton.title = 'Download every gauge\'s recorded heights';
+        exportButton.addEventListener('click', () => this.exportTideGauges());
+        groupDiv.appendChild(exportButton);
+        probeControls.appendChild(groupDiv);
+
+        this.renderProbeList();
+    }
+
+    // Rebuilds the editor for the simulator's current tide gauges. Moving
+    // or removing a gauge replaces the set, which restarts every record.
+    renderProbeList() {
+        if (!this.probeList) return;
+        this.probeList.innerHTML = '';
+
+        const probes = this.app.fluidSimulator.getProbes();
+        probes.forEach((probe, index) => {
+            const row = document.createElement('div');
+            row.className = 'control-item force-item';
+            const label = document.createElement('label');
+            label.textContent = probe.name;
+            row.appendChild(label);
+
+            ['latitude', 'longitude'].forEach(field => {
+                const input = document.createElement('input');
+                input.type = 'number';
+                input.step = 1;
+                input.value = probe[field];
+                input.className = 'vector-input';
+                input.title = `${field} (degrees)`;
+                input.addEventListener('change', (e) => {
+                    probe[field] = parseFloat(e.target.value) || 0;
+                    this.app.fluidSimulator.setProbes(probes);
+                });
+                row.appendChild(input);
+            });
+
+            const removeButton = document.createElement('button');
+            removeButton.textContent = 'Remove';
+            removeButton.addEventListener('click', () => {
+                probes.splice(index, 1);
+                this.app.fluidSimulator.setProbes(probes);
+                this.renderProbeList();
+            });
+            row.appendChild(removeButton);
+
+            this.probeList.appendChild(row);
+        });
+    }
+
+    exportTideGauges() {
+        downloadCsv('tide-gauges.csv', tideGaugeCsv(this.app.fluidSimulator.tideGauges));
+    }
+
+    createChartControls() {
+        const chartControls = document.getElementById('chart-controls');
+        if (!chartControls) return;
+
+        const groupDiv = document.createElement('div');
+        groupDiv.className = 'control-group';
+        const groupTitle = document.createElement('h4');
+        groupTitle.textContent = 'Charts';
+        groupDiv.appendChild(groupTitle);
+        chartControls.appendChild(groupDiv);
+
+        this.charts = new DiagnosticsCharts(groupDiv, this.app.fluidSimulator);
+    }
+
+    createSelectControl(control) {
+        const select = document.createElement('select');
+        select.className = 'select-input';
+        select.setAttribute('data-param', control.name);
+        select.title = control.tooltip;
+
+        control.options.forEach(({ value, label }) => {
+            const option = document.createElement('option');
+            option.value = value;
+            option.textContent = label;
+            select.appendChild(option);
+        });
+        select.value = control.default;
+
+        select.addEventListener('change', (e) => {
+            // Option values come back as strings; hand over the typed value
+            const option = control.options.find(({ value }) => String(value) === e.target.value);
+            this.app.fluidSimulator.setParameter(control.name, option ? option.value : e.target.value);
+        });
+
+        return select;
+    }
+
     toggleSimulation() {
         this.isPlaying = !this.isPlaying;
         const button = document.getElementById('play-pause');
         button.textContent = this.isPlaying ? 'Pause' : 'Play';
         
-        // Update simulator and animation state
-        this.app.fluidSimulator.setParameter('timeScale', this.isPlaying ? 1.0 : 0.0);
+        // Resume at the time scale the control shows, including any edit
+        // made while paused
+        if (this.isPlaying) {
+            const timeScaleInput = document.querySelector('[data-param="timeScale"]');
+            if (timeScaleInput) {
+                this.app.fluidSimulator.setParameter('timeScale', parseFloat(timeScaleInput.value));
+            }
+        }
         this.app.toggleAnimation(this.isPlaying);
     }
 
@@ -245,18 +806,17 @@ export class UIController {
             this.toggleSimulation();
         }
         
-        // Reset all controls to their default values
-        Object.entries(this.app.fluidSimulator.parameters).forEach(([name, value]) => {
-            const control = document.querySelector(`input[data-param="${name}"]`);
-            if (control && control.dataset.default) {
-                const defaultValue = parseFloat(control.dataset.default);
-                this.app.fluidSimulator.setParameter(name, defaultValue);
-                this.updateControlValue(name, defaultValue);
-            }
-        });
-        
-        // Reset particles to initial state
+        // Reset parameters and particles to their initial state, then show
+        // the core's defaults in every control, selects included
         this.app.fluidSimulator.resetToDefault();
+        Object.entries(this.app.fluidSimulator.defaultParameters).forEach(([name, value]) => {
+            this.updateControlValue(name, value);
+        });
+        this.renderForceList();
+        this.renderProbeList();
+        if (this.charts) {
+            this.charts.clear();
+        }
         
         // Resume if it was playing
         if (wasPlaying) {
@@ -275,13 +835,15 @@ export class UIController {
         if (scenario) {
             // Update simulator parameters in batches
             requestAnimationFrame(() => {
+                this.app.fluidSimulator.applyScenario(scenario, seed);
                 Object.entries(scenario.parameters).forEach(([name, value]) => {
-                    this.app.fluidSimulator.setParameter(name, value);
                     this.updateControlValue(name, value);
                 });
-                
-                // Reset simulation with new parameters
-                this.app.fluidSimulator.initializeParticles();
+                this.renderForceList();
+                this.renderProbeList();
+                if (this.charts) {
+                    this.charts.clear();
+                }
                 
                 // Resume if it was playing
                 if (wasPlaying) {
@@ -296,8 +858,26 @@ export class UIController {
         }
     }
 
+    // Shows the simulation clock and the adaptive step size actually in use,
+    // and warns once substeps had to run past the stability limit
+    updateStatus() {
+        const status = document.getElementById('simulation-status');
+        if (!status) return;
+
+        const simulator = this.app.fluidSimulator;
+        const stepMs = simulator.effectiveTimeStep * 1000;
+        let text = `t = ${simulator.simulationTime.toFixed(2)} s | dt = ${stepMs.toFixed(2)} ms`;
+        if (simulator.clampedSubsteps > 0) {
+            text += ` | ${simulator.clampedSubsteps} unstable substeps`;
+            status.title = 'Substeps that ran longer than the stability limit allows. Raise Max Adaptive Substeps or lower the fixed time step.';
+        } else {
+            status.title = '';
+        }
+        status.textContent = text;
+    }
+
     updateControlValue(name, value) {
-        const input = document.querySelector(`input[data-param="${name}"]`);
+        const input = document.querySelector(`[data-param="${name}"]`);
         if (input) {
             input.value = value;
         }
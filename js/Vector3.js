@@ -0,0 +1,102 @@
+// Minimal 3D vector for the simulation core. It mirrors the subset of the
+// THREE.Vector3 API the physics uses, so the core runs without Three.js
+// (in Node, in workers) and values can be copied straight into meshes.
+export class Vector3 {
+    constructor(x = 0, y = 0, z = 0) {
+        this.x = x;
+        this.y = y;
+        this.z = z;
+    }
+
+    set(x, y, z) {
+        this.x = x;
+        this.y = y;
+        this.z = z;
+        return this;
+    }
+
+    copy(v) {
+        this.x = v.x;
+        this.y = v.y;
+        this.z = v.z;
+        return this;
+    }
+
+    clone() {
+        return new Vector3(this.x, this.y, this.z);
+    }
+
+    add(v) {
+        this.x += v.x;
+        this.y += v.y;
+        this.z += v.z;
+        return this;
+    }
+
+    sub(v) {
+        this.x -= v.x;
+        this.y -= v.y;
+        this.z -= v.z;
+        return this;
+    }
+
+    addScaledVector(v, s) {
+        this.x += v.x * s;
+        this.y += v.y * s;
+        this.z += v.z * s;
+        return this;
+    }
+
+    multiplyScalar(s) {
+        this.x *= s;
+        this.y *= s;
+        this.z *= s;
+        return this;
+    }
+
+    divideScalar(s) {
+        return this.multiplyScalar(1 / s);
+    }
+
+    cross(v) {
+        const x = this.y * v.z - this.z * v.y;
+        const y = this.z * v.x - this.x * v.z;
+        const z = this.x * v.y - this.y * v.x;
+        return this.set(x, y, z);
+    }
+
+    // Rotates about a unit axis by `angle` radians (Rodrigues' formula)
+    applyAxisAngle(axis, angle) {
+        const cos = Math.cos(angle);
+        const sin = Math.sin(angle);
+        const along = this.dot(axis) * (1 - cos);
+        const cx = axis.y * this.z - axis.z * this.y;
+        const cy = axis.z * this.x - axis.x * this.z;
+        const cz = axis.x * this.y - axis.y * this.x;
+        return this.set(
+            this.x * cos + cx * sin + axis.x * along,
+            this.y * cos + cy * sin + axis.y * along,
+            this.z * cos + cz * sin + axis.z * along);
+    }
+
+    dot(v) {
+        return this.x * v.x + this.y * v.y + this.z * v.z;
+    }
+
+    lengthSq() {
+        return this.x * this.x + this.y * this.y + this.z * this.z;
+    }
+
+    length() {
+        return Math.sqrt(this.lengthSq());
+    }
+
+    normalize() {
+        return this.divideScalar(this.length() || 1);
+    }
+
+    distanceTo(v) {
+        const dx = this.x - v.x, dy = this.y - v.y, dz = this.z - v.z;
+        return Math.sqrt(dx * dx + dy * dy + dz * dz);
+    }
+}
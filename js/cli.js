@@ -0,0 +1,214 @@
+#!/usr/bin/env node
+// Headless batch runner: loads a scenario, runs the simulation core for a
+// fixed number of physics steps or simulated seconds and writes diagnostics
+// (and optionally snapshots) to disk. Scenarios with tide gauges also get
+// their per-step heights in tide-gauges.csv.
+//
+//   node js/cli.js --scenario <seed|file.json> --seconds 600 --out runs/a
+//   node js/cli.js --steps 5000 --snapshot-every 500
+import { readFileSync, writeFileSync, mkdirSync, existsSync } from 'node:fs';
+import { join } from 'node:path';
+import { pathToFileURL } from 'node:url';
+import { SimulationCore } from './SimulationCore.js';
+import { ScenarioManager } from './ScenarioManager.js';
+import { measureDiagnostics, takeSnapshot, toCsv, DIAGNOSTIC_COLUMNS } from './Diagnostics.js';
+import { tideGaugeCsv } from './TideGauges.js';
+import { createNodeForcePool } from './NodeForcePool.js';
+
+const USAGE = `Usage: node js/cli.js [options]
+
+  --scenario <seed|file>   Scenario seed string or path to a scenario JSON file
+  --steps <n>              Number of fixed physics steps to run
+  --seconds <t>            Simulated seconds to run (ignored if --steps is given)
+  --out <dir>              Output directory (default: output)
+  --every <n>              Record diagnostics every n steps (default: 10)
+  --snapshot-every <n>     Also write a full snapshot every n steps
+  --threads <n>            Worker threads for the particle passes (default: one
+                           per spare core; 0 or 1 runs single-threaded)
+  --quiet                  Don't print progress
+  --help                   Show this message`;
+
+const OPTIONS = {
+    '--scenario': 'scenario',
+    '--steps': 'steps',
+    '--seconds': 'seconds',
+    '--out': 'out',
+    '--every': 'every',
+    '--snapshot-every': 'snapshotEvery',
+    '--threads': 'threads'
+};
+
+export function parseArguments(argv) {
+    const options = { out: 'output', every: '10', quiet: false, help: false };
+
+    for (let i = 0; i < argv.length; i++) {
+        const flag = argv[i];
+        if (flag === '--help' || flag === '-h') {
+            options.help = true;
+        } else if (flag === '--quiet') {
+            options.quiet = true;
+        } else if (flag in OPTIONS) {
+            if (i + 1 >= argv.length) {
+                throw new Error(`Missing value for ${flag}`);
+            }
+            options[OPTIONS[flag]] = argv[++i];
+        } else {
+            throw new Error(`Unknown option: ${flag}`);
+        }
+    }
+
+    for (const name of ['steps', 'seconds', 'every', 'snapshotEvery']) {
+        if (options[name] !== undefined) {
+            const value = Number(options[name]);
+            if (!(value > 0)) {
+                throw new Error(`--${name.replace(/[A-Z]/g, c => '-' + c.toLowerCase())} must be a positive number`);
+            }
+            options[name] = value;
+        }
+    }
+    options.threads = parseThreads(options.threads);
+    return options;
+}
+
+// Worker count from a --threads value; undefined leaves the choice to
+// NodeForcePool
+export function parseThreads(value) {
+    if (value === undefined) return undefined;
+    const threads = Number(value);
+    if (!Number.isInteger(threads) || threads < 0) {
+        throw new Error('--threads must be a whole number, 0 or more');
+    }
+    return threads;
+}
+
+// A scenario argument is either a JSON file or an encoded scenario seed
+export function readScenario(source, scenarioManager = new ScenarioManager()) {
+    if (existsSync(source)) {
+        const scenario = JSON.parse(readFileSync(source, 'utf8'));
+        scenario.objects = scenario.objects || [];
+        scenario.forces = scenario.forces || [];
+        scenario.probes = scenario.probes || [];
+        scenario.parameters = scenario.parameters || {};
+        scenarioManager.validateScenarioData(scenario);
+        return { scenario, seed: scenario.seed ?? source };
+    }
+
+    const scenario = scenarioManager.decodeSeed(source);
+    scenarioManager.validateScenarioData(scenario);
+    return { scenario, seed: source };
+}
+
+// Runs one scenario and returns the recorded diagnostics. `onRecord` is
+// called with every row and `onSnapshot` with every snapshot as they are
+// produced. With a ForcePool the particle passes run on its workers; the
+// pool can be reused for the next run.
+export function runScenario(scenario, { steps, seconds, every = 10, snapshotEvery, seed, pool = null, onRecord, onSnapshot } = {}) {
+    const core = new SimulationCore();
+    if (pool) {
+        // The workers' mirror cores may still hold an earlier run
+        pool.forward('resetToDefault', []);
+        core.setForcePool(pool);
+    }
+    if (scenario) {
+        core.applyScenario(scenario, seed);
+        if (pool) {
+            pool.forward('applyScenario', [scenario, seed]);
+        }
+    }
+
+    const fixedTimeStep = core.parameters.fixedTimeStep;
+    const totalSteps = steps ?? Math.round(seconds / fixedTimeStep);
+    const rows = [];
+
+    const record = (step) => {
+        const row = { step, ...measureDiagnostics(core) };
+        rows.push(row);
+        if (onRecord) onRecord(row, totalSteps);
+    };
+
+    record(0);
+    try {
+        for (let step = 1; step <= totalSteps; step++) {
+            core.step(fixedTimeStep);
+
+            if (step % every === 0 || step === totalSteps) {
+                record(step);
+            }
+            if (snapshotEvery && onSnapshot && step % snapshotEvery === 0) {
+                onSnapshot(step, takeSnapshot(core));
+            }
+        }
+    } finally {
+        if (pool) {
+            pool.endSession();
+        }
+    }
+
+    return { core, rows };
+}
+
+function main(argv) {
+    const options = parseArguments(argv);
+    if (options.help) {
+        console.log(USAGE);
+        return;
+    }
+    if (options.steps === undefined && options.seconds === undefined) {
+        throw new Error('Give either --steps or --seconds\n\n' + USAGE);
+    }
+
+    const loaded = options.scenario ? readScenario(options.scenario) : { scenario: null };
+    mkdirSync(options.out, { recursive: true });
+
+    const started = Date.now();
+    const pool = createNodeForcePool(options.threads);
+    let result;
+    try {
+        result = runScenario(loaded.scenario, {
+            steps: options.steps,
+            seconds: options.seconds,
+            every: options.every,
+            snapshotEvery: options.snapshotEvery,
+            seed: loaded.seed,
+            pool,
+            onRecord: (row, totalSteps) => {
+                if (!options.quiet) {
+                    process.stderr.write(`\rstep ${row.step}/${totalSteps}  t = ${row.time.toFixed(2)} s  ` +
+                        `E = ${row.totalEnergy.toExponential(4)}  bulge = ${row.bulgeAmplitude.toFixed(4)}`);
+                }
+            },
+            onSnapshot: (step, snapshot) => {
+                const name = `snapshot-${String(step).padStart(8, '0')}.json`;
+                writeFileSync(join(options.out, name), JSON.stringify(snapshot));
+            }
+        });
+    } finally {
+        if (pool) {
+            pool.terminate();
+        }
+    }
+    const { core, rows } = result;
+
+    writeFileSync(join(options.out, 'diagnostics.csv'), toCsv(rows, ['step', ...DIAGNOSTIC_COLUMNS]));
+    if (core.tideGauges.length > 0) {
+        writeFileSync(join(options.out, 'tide-gauges.csv'), tideGaugeCsv(core.tideGauges));
+    }
+    if (!options.quiet) {
+        const seconds = ((Date.now() - started) / 1000).toFixed(1);
+        process.stderr.write(`\nWrote ${rows.length} rows to ${join(options.out, 'diagnostics.csv')} in ${seconds} s\n`);
+    }
+    if (core.clampedSubsteps > 0) {
+        process.stderr.write(`Warning: ${core.clampedSubsteps} substeps ran longer than the stability limit ` +
+            `allows; raise maxAdaptiveSubsteps or lower fixedTimeStep\n`);
+    }
+}
+
+// Only run when executed directly, so the helpers can be imported
+if (import.meta.url === pathToFileURL(process.argv[1]).href) {
+    try {
+        main(process.argv.slice(2));
+    } catch (error) {
+        console.error(error.message);
+        process.exitCode = 1;
+    }
+}
@@ -14,8 +14,7 @@ class SimulationApp {
         });
         
         this.fluidSimulator = new FluidSimulator();
-        // Set initial timeScale to 0 to pause simulation
-        this.fluidSimulator.setParameter('timeScale', 0.0);
+        this.lastFrameTime = null;
         
         // Add handler for particle system updates
         this.fluidSimulator.onParticleSystemUpdate = (newParticleSystem) => {
@@ -45,9 +44,7 @@ class SimulationApp {
         
         // Add all objects to the scene
         const objects = this.fluidSimulator.getObjects();
-        this.scene.add(objects.planet);
-        this.scene.add(objects.moon);
-        this.scene.add(objects.orbitLine);
+        this.scene.add(objects.bodies);
         this.scene.add(objects.particles);
         
         this.animate();
@@ -84,18 +81,40 @@ class SimulationApp {
         const secondaryLight = new THREE.DirectionalLight(0x404040, 0.5);
         secondaryLight.position.set(-10, -10, -10);
         
-        this.scene.add(ambientLight, directionalLight, secondaryLight);
+        this.scene.add(ambientLight, directionalLight, secondaryLight, directionalLight.target);
+        this.keyLight = directionalLight;
+    }
+
+    // With a sun in the system (and sunLight on) the key light shines from
+    // it onto the planet, otherwise from its fixed corner
+    updateKeyLight() {
+        const { sun, primaryBody } = this.fluidSimulator.core;
+        if (sun && this.fluidSimulator.parameters.sunLight) {
+            this.keyLight.position.copy(sun.position);
+            this.keyLight.target.position.copy(primaryBody.position);
+        } else {
+            this.keyLight.position.set(10, 10, 10);
+            this.keyLight.target.position.set(0, 0, 0);
+        }
     }
 
     animate() {
         // Use RAF ID to potentially cancel animation
         this.animationId = requestAnimationFrame(() => this.animate());
         
-        // Only update if simulation is actually running
-        if (this.fluidSimulator.parameters.timeScale > 0) {
-            this.fluidSimulator.update();
+        // Measure wall-clock time since the previous frame
+        const now = performance.now();
+        const frameDeltaSeconds = this.lastFrameTime === null ? 0 : (now - this.lastFrameTime) / 1000;
+        this.lastFrameTime = now;
+        
+        // Only step while playing; the loop also runs before the first Play
+        if (this.uiController.isPlaying) {
+            this.fluidSimulator.update(frameDeltaSeconds);
         }
         
+        this.updateKeyLight();
+        this.uiController.updateStatus();
+        
         // Only update controls if they're being used
         if (this.controls.enabled && this.controls.isDragging) {
             this.controls.update();
@@ -115,6 +134,8 @@ class SimulationApp {
         if (!isPlaying && this.animationId) {
             cancelAnimationFrame(this.animationId);
             this.animationId = null;
+            // Don't count the paused interval as simulated time on resume
+            this.lastFrameTime = null;
         } else if (isPlaying && !this.animationId) {
             this.animate();
         }
@@ -0,0 +1,181 @@
+#!/usr/bin/env node
+// Parameter sweeps and ensembles: expands a base scenario over parameter
+// values, runs every combination headlessly and collects one summary row
+// per run into results.csv and results.json.
+//
+//   node js/sweep.js --sweep sweeps/moon.json --seconds 120 --out runs/moon
+//
+// The sweep file looks like
+//
+//   {
+//     "base": { "seed": "tides", "parameters": { ... }, "objects": [], "forces": [] },
+//     "grid": { "moonMass": [50, 100, 200], "viscosity": [0.5, 1.0] },
+//     "replicates": 3,
+//     "seconds": 120
+//   }
+//
+// with "list": [{ "moonMass": 50 }, ...] instead of "grid" for explicit
+// parameter sets. --steps/--seconds on the command line override the file.
+import { readFileSync, writeFileSync, mkdirSync } from 'node:fs';
+import { join } from 'node:path';
+import { pathToFileURL } from 'node:url';
+import { ScenarioManager } from './ScenarioManager.js';
+import { summarizeDiagnostics, toCsv, SUMMARY_COLUMNS, DIAGNOSTIC_COLUMNS } from './Diagnostics.js';
+import { runScenario, parseThreads } from './cli.js';
+import { createNodeForcePool } from './NodeForcePool.js';
+
+const USAGE = `Usage: node js/sweep.js --sweep <file.json> [options]
+
+  --sweep <file>           Sweep definition (base scenario plus grid or list)
+  --steps <n>              Fixed physics steps per run
+  --seconds <t>            Simulated seconds per run
+  --out <dir>              Output directory (default: output/sweep)
+  --every <n>              Diagnostics sampling interval in steps (default: 10)
+  --keep-runs              Also write each run's full diagnostics CSV
+  --threads <n>            Worker threads for the particle passes (default: one
+                           per spare core; 0 or 1 runs single-threaded)
+  --quiet                  Don't print progress
+  --help                   Show this message`;
+
+export function parseArguments(argv) {
+    const options = { out: join('output', 'sweep'), every: 10, keepRuns: false, quiet: false, help: false };
+    const valued = {
+        '--sweep': 'sweep', '--steps': 'steps', '--seconds': 'seconds', '--out': 'out', '--every': 'every',
+        '--threads': 'threads'
+    };
+
+    for (let i = 0; i < argv.length; i++) {
+        const flag = argv[i];
+        if (flag === '--help' || flag === '-h') {
+            options.help = true;
+        } else if (flag === '--keep-runs') {
+            options.keepRuns = true;
+        } else if (flag === '--quiet') {
+            options.quiet = true;
+        } else if (flag in valued) {
+            if (i + 1 >= argv.length) {
+                throw new Error(`Missing value for ${flag}`);
+            }
+            options[valued[flag]] = argv[++i];
+        } else {
+            throw new Error(`Unknown option: ${flag}`);
+        }
+    }
+
+    for (const name of ['steps', 'seconds', 'every']) {
+        if (typeof options[name] === 'string') {
+            options[name] = Number(options[name]);
+            if (!(options[name] > 0)) {
+                throw new Error(`--${name} must be a positive number`);
+            }
+        }
+    }
+    options.threads = parseThreads(options.threads);
+    return options;
+}
+
+// Runs every scenario of a sweep and returns one row per run: the swept
+// values, the replicate index and the summary metrics. Runs go one after
+// another, each split across the pool's workers when one is given.
+export function runSweep(definition, { steps, seconds, every = 10, pool = null, onRun } = {}) {
+    const scenarioManager = new ScenarioManager();
+    const runs = scenarioManager.createSweep(definition.base || {}, definition);
+    const results = [];
+
+    runs.forEach((run, index) => {
+        const scenario = scenarioManager.decodeSeed(run.scenario);
+        const started = Date.now();
+        const { rows } = runScenario(scenario, {
+            steps: steps ?? definition.steps,
+            seconds: seconds ?? definition.seconds,
+            every,
+            pool
+        });
+
+        const result = {
+            run: run.run,
+            replicate: run.replicate,
+            seed: scenario.seed,
+            ...run.values,
+            ...summarizeDiagnostics(rows),
+            wallSeconds: (Date.now() - started) / 1000
+        };
+        results.push(result);
+        if (onRun) onRun(result, rows, run.values, index, runs.length);
+    });
+
+    return results;
+}
+
+function main(argv) {
+    const options = parseArguments(argv);
+    if (options.help || !options.sweep) {
+        console.log(USAGE);
+        if (!options.help) process.exitCode = 1;
+        return;
+    }
+
+    const definition = JSON.parse(readFileSync(options.sweep, 'utf8'));
+    if (options.steps === undefined && options.seconds === undefined &&
+        definition.steps === undefined && definition.seconds === undefined) {
+        throw new Error('Give a run length with --steps/--seconds or in the sweep file');
+    }
+    mkdirSync(options.out, { recursive: true });
+
+    const pool = createNodeForcePool(options.threads);
+    let results;
+    try {
+        results = runSweep(definition, {
+            steps: options.steps,
+            seconds: options.seconds,
+            every: options.every,
+            pool,
+            onRun: (result, rows, values, index, total) => {
+                if (options.keepRuns) {
+                    const name = `run-${String(result.run).padStart(4, '0')}-${result.replicate}.csv`;
+                    writeFileSync(join(options.out, name), toCsv(rows, ['step', ...DIAGNOSTIC_COLUMNS]));
+                }
+                if (!options.quiet) {
+                    process.stderr.write(`run ${index + 1}/${total} ${JSON.stringify(values)}` +
+                        `  bulge = ${result.meanBulgeAmplitude.toFixed(4)}  (${result.wallSeconds.toFixed(1)} s)\n`);
+                }
+                if (result.clampedSubsteps > 0) {
+                    process.stderr.write(`Warning: run ${index + 1} took ${result.clampedSubsteps} substeps ` +
+                        `longer than the stability limit allows\n`);
+                }
+            }
+        });
+    } finally {
+        if (pool) {
+            pool.terminate();
+        }
+    }
+
+    // Columns: identifiers, swept parameters in first-seen order, metrics
+    const sweptColumns = [];
+    for (const result of results) {
+        for (const name of Object.keys(result)) {
+            if (!sweptColumns.includes(name) && !['run', 'replicate', 'seed', 'wallSeconds'].includes(name) &&
+                !SUMMARY_COLUMNS.includes(name)) {
+                sweptColumns.push(name);
+            }
+        }
+    }
+    const columns = ['run', 'replicate', 'seed', ...sweptColumns, ...SUMMARY_COLUMNS, 'wallSeconds'];
+
+    writeFileSync(join(options.out, 'results.csv'), toCsv(results, columns));
+    writeFileSync(join(options.out, 'results.json'), JSON.stringify(results, null, 2));
+    if (!options.quiet) {
+        process.stderr.write(`Wrote ${results.length} runs to ${options.out}\n`);
+    }
+}
+
+// Only run when executed directly, so runSweep can be imported
+if (import.meta.url === pathToFileURL(process.argv[1]).href) {
+    try {
+        main(process.argv.slice(2));
+    } catch (error) {
+        console.error(error.message);
+        process.exitCode = 1;
+    }
+}
@@ -0,0 +1,80 @@
+import { test } from 'node:test';
+import assert from 'node:assert/strict';
+import { BarnesHutTree } from '../js/BarnesHut.js';
+import { SeededRandom } from '../js/SeededRandom.js';
+
+const COUNT = 400;
+const MASS = 0.5;
+const SOFTENING = 0.1;
+
+function randomPositions(seed) {
+    const random = new SeededRandom(seed);
+    const positions = new Float64Array(COUNT * 3);
+    for (let k = 0; k < positions.length; k++) {
+        positions[k] = random.next() * 10 - 5;
+    }
+    return positions;
+}
+
+// Softened pull and potential of every particle at a point, summed directly
+function directSum(positions, x, y, z) {
+    const out = { x: 0, y: 0, z: 0, potential: 0 };
+    for (let j = 0; j < COUNT; j++) {
+        const dx = positions[j * 3] - x;
+        const dy = positions[j * 3 + 1] - y;
+        const dz = positions[j * 3 + 2] - z;
+        const inverse = 1 / Math.sqrt(dx * dx + dy * dy + dz * dz + SOFTENING * SOFTENING);
+        out.x += MASS * dx * inverse ** 3;
+        out.y += MASS * dy * inverse ** 3;
+        out.z += MASS * dz * inverse ** 3;
+        out.potential -= MASS * inverse;
+    }
+    return out;
+}
+
+test('a zero opening angle reproduces the direct sum', () => {
+    const positions = randomPositions('exact');
+    const tree = new BarnesHutTree().build(positions, COUNT, MASS);
+
+    for (const i of [0, 17, 250]) {
+        const [x, y, z] = positions.subarray(i * 3, i * 3 + 3);
+        const out = { x: 0, y: 0, z: 0 };
+        const potential = tree.accelerationAt(x, y, z, 0, SOFTENING, out);
+        const expected = directSum(positions, x, y, z);
+        for (const axis of ['x', 'y', 'z']) {
+            assert.ok(Math.abs(out[axis] - expected[axis]) < 1e-9 * Math.abs(expected.potential));
+        }
+        assert.ok(Math.abs(potential - expected.potential) < 1e-9 * Math.abs(expected.potential));
+    }
+});
+
+test('the approximation stays close to the direct sum', () => {
+    const positions = randomPositions('approximate');
+    const tree = new BarnesHutTree().build(positions, COUNT, MASS);
+
+    for (const [x, y, z] of [[0, 0, 0], [3, -2, 1], [20, 5, -8]]) {
+        const out = { x: 0, y: 0, z: 0 };
+        tree.accelerationAt(x, y, z, 0.5, SOFTENING, out);
+        const expected = directSum(positions, x, y, z);
+        const error = Math.hypot(out.x - expected.x, out.y - expected.y, out.z - expected.z);
+        assert.ok(error < 0.02 * Math.hypot(expected.x, expected.y, expected.z), `error ${error} at ${[x, y, z]}`);
+    }
+});
+
+test('rebuilding reuses the tree for a new set of particles', () => {
+    const tree = new BarnesHutTree();
+    tree.build(randomPositions('first'), COUNT, MASS);
+    const positions = randomPositions('second');
+    tree.build(positions, COUNT, MASS);
+
+    const out = { x: 0, y: 0, z: 0 };
+    const potential = tree.accelerationAt(1, 1, 1, 0, SOFTENING, out);
+    assert.ok(Math.abs(potential - directSum(positions, 1, 1, 1).potential) < 1e-9 * Math.abs(potential));
+});
+
+test('an empty tree exerts no pull', () => {
+    const tree = new BarnesHutTree().build(new Float64Array(0), 0, MASS);
+    const out = { x: 0, y: 0, z: 0 };
+    assert.equal(tree.accelerationAt(1, 2, 3, 0.5, SOFTENING, out), 0);
+    assert.deepEqual(out, { x: 0, y: 0, z: 0 });
+});
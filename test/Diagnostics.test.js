@@ -0,0 +1,30 @@
+import { test } from 'node:test';
+import assert from 'node:assert/strict';
+import { SimulationCore } from '../js/SimulationCore.js';
+import { measureDiagnostics } from '../js/Diagnostics.js';
+
+test('measuring leaves the core state untouched', () => {
+    const core = new SimulationCore();
+    core.setParameter('particleCount', 200);
+    core.setParameter('sunEnabled', true);
+    core.tidalReference.set(1, 2, 3);
+
+    const diagnostics = measureDiagnostics(core);
+
+    assert.ok(Number.isFinite(diagnostics.totalEnergy));
+    assert.deepEqual([core.tidalReference.x, core.tidalReference.y, core.tidalReference.z], [1, 2, 3]);
+});
+
+test('repeated measurements agree, self-gravity included', () => {
+    const core = new SimulationCore();
+    core.setParameter('particleCount', 200);
+    core.setParameter('selfGravity', true);
+
+    const first = measureDiagnostics(core);
+    const other = new SimulationCore();
+    other.setParameter('particleCount', 50);
+    other.setParameter('selfGravity', true);
+    measureDiagnostics(other);
+
+    assert.deepEqual(measureDiagnostics(core), first);
+});
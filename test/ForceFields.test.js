@@ -0,0 +1,58 @@
+import { test } from 'node:test';
+import assert from 'node:assert/strict';
+import { createForceField, applyForceFields } from '../js/ForceFields.js';
+
+const planet = { position: { x: 0, y: 0, z: 0 }, velocity: { x: 1, y: 0, z: 0 } };
+const context = {
+    bodies: new Map([['planet', planet], ['moon', { position: { x: 10, y: 0, z: 0 } }]]),
+    primaryBody: planet,
+    surfaceRadius: 5,
+    neighbors: null
+};
+
+// Acceleration the given force entries add at a point
+function accelerationOf(definitions, position, velocity = { x: 0, y: 0, z: 0 }) {
+    const out = { x: 0, y: 0, z: 0 };
+    applyForceFields(definitions.map(createForceField), position, velocity, context, out);
+    return out;
+}
+
+function assertVector(actual, expected) {
+    for (const axis of ['x', 'y', 'z']) {
+        assert.ok(Math.abs(actual[axis] - expected[axis]) < 1e-12, `${axis}: ${actual[axis]} vs ${expected[axis]}`);
+    }
+}
+
+test('createForceField fills in defaults and copies vectors', () => {
+    const direction = [0, 0, 2];
+    const field = createForceField({ type: 'uniform', direction });
+    assert.deepEqual(field, { type: 'uniform', enabled: true, direction: [0, 0, 2], strength: 1.0 });
+    assert.notEqual(field.direction, direction);
+    assert.throws(() => createForceField({ type: 'tornado' }), /Unknown force field type/);
+});
+
+test('a uniform field pushes along its normalized direction', () => {
+    assertVector(accelerationOf([{ type: 'uniform', direction: [0, 0, 2], strength: 3 }], { x: 7, y: 7, z: 7 }),
+        { x: 0, y: 0, z: 3 });
+});
+
+test('a radial field falls off with distance from a body', () => {
+    const field = { type: 'radial', body: 'moon', strength: 8, falloff: 2 };
+    assertVector(accelerationOf([field], { x: 12, y: 0, z: 0 }), { x: 2, y: 0, z: 0 });
+});
+
+test('wind only acts near the surface and along it', () => {
+    const wind = { type: 'wind', direction: [1, 1, 0], strength: 2, depth: 0.5 };
+    assertVector(accelerationOf([wind], { x: 0, y: 4.8, z: 0 }), { x: 2, y: 0, z: 0 });
+    assertVector(accelerationOf([wind], { x: 0, y: 4, z: 0 }), { x: 0, y: 0, z: 0 });
+});
+
+test('drag opposes motion relative to the planet', () => {
+    const drag = { type: 'drag', coefficient: 0.5 };
+    assertVector(accelerationOf([drag], { x: 5, y: 0, z: 0 }, { x: 3, y: 2, z: 0 }), { x: -1, y: -1, z: 0 });
+});
+
+test('disabled fields add nothing and fields add up', () => {
+    const up = { type: 'uniform', direction: [0, 1, 0], strength: 1 };
+    assertVector(accelerationOf([up, { ...up, enabled: false }, up], { x: 0, y: 0, z: 0 }), { x: 0, y: 2, z: 0 });
+});
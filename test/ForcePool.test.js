@@ -0,0 +1,90 @@
+import { test } from 'node:test';
+import assert from 'node:assert/strict';
+import { ForcePool } from '../js/ForcePool.js';
+import { createNodeForcePool } from '../js/NodeForcePool.js';
+import { SimulationCore } from '../js/SimulationCore.js';
+import { SeededRandom } from '../js/SeededRandom.js';
+
+// A pool whose workers are never started, for the coordinator-side logic
+function idlePool(size) {
+    return new ForcePool(size, () => ({ postMessage() {}, terminate() {} }));
+}
+
+function slabOf(pool, cell) {
+    for (let w = 0; w < pool.size; w++) {
+        if (cell >= pool.slabs[w * 2] && cell < pool.slabs[w * 2 + 1]) return w;
+    }
+    return -1;
+}
+
+test('partition gives every particle exactly one balanced slab', () => {
+    const random = new SeededRandom('partition');
+    const count = 2000;
+    const positions = new Float64Array(count * 3);
+    for (let k = 0; k < positions.length; k++) {
+        positions[k] = random.next() * 40 - 20;
+    }
+
+    const pool = idlePool(4);
+    pool.partition(positions, count, 1);
+
+    assert.equal(pool.slabs[0], -0x80000000);
+    assert.equal(pool.slabs[pool.size * 2 - 1], 0x7fffffff);
+    for (let w = 1; w < pool.size; w++) {
+        assert.equal(pool.slabs[w * 2], pool.slabs[w * 2 - 1], 'slabs must be contiguous');
+    }
+
+    const owned = new Array(pool.size).fill(0);
+    for (let i = 0; i < count; i++) {
+        const w = slabOf(pool, Math.floor(positions[i * 3]));
+        assert.notEqual(w, -1);
+        owned[w]++;
+    }
+    // Whole cells hold about count / 40 particles each
+    for (const n of owned) {
+        assert.ok(Math.abs(n - count / pool.size) < 2 * count / 40, `unbalanced slabs ${owned}`);
+    }
+});
+
+test('partition copes with fewer occupied cells than workers', () => {
+    const positions = new Float64Array([0.5, 0, 0, 0.6, 0, 0, 0.7, 0, 0]);
+    const pool = idlePool(4);
+    pool.partition(positions, 3, 1);
+
+    for (let w = 0; w < pool.size; w++) {
+        assert.ok(pool.slabs[w * 2] <= pool.slabs[w * 2 + 1]);
+    }
+    assert.notEqual(slabOf(pool, 0), -1);
+});
+
+test('pooled stepping matches single-threaded stepping bit for bit', async (t) => {
+    const setup = [
+        ['setParameter', ['particleCount', 600]],
+        ['setParameter', ['rotatingFrame', true]],
+        ['setSeed', ['pool']],
+        ['initializeParticles', []]
+    ];
+
+    const serial = new SimulationCore();
+    const pooled = new SimulationCore();
+    const pool = createNodeForcePool(2);
+    t.after(() => pool.terminate());
+    pooled.setForcePool(pool);
+    for (const [method, args] of setup) {
+        serial[method](...args);
+        pooled[method](...args);
+        pool.forward(method, args);
+    }
+    assert.deepEqual(pooled.particles.positions, serial.particles.positions);
+
+    for (let i = 0; i < 20; i++) {
+        serial.step(serial.parameters.fixedTimeStep);
+        pooled.step(pooled.parameters.fixedTimeStep);
+    }
+    pool.endSession();
+
+    assert.deepEqual(pooled.particles.positions, serial.particles.positions);
+    assert.deepEqual(pooled.particles.velocities, serial.particles.velocities);
+    assert.deepEqual(pooled.particles.accelerations, serial.particles.accelerations);
+    assert.deepEqual(pooled.particles.densities, serial.particles.densities);
+});
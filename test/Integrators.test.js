@@ -0,0 +1,85 @@
+import { test } from 'node:test';
+import assert from 'node:assert/strict';
+import { integrators, getIntegrator, leapfrog } from '../js/Integrators.js';
+
+// A unit-mass particle on a circular orbit around a unit point mass at the
+// origin, the same kind of block the simulation core hands over
+function createOrbit() {
+    const block = {
+        count: 1,
+        positions: new Float64Array([1, 0, 0]),
+        velocities: new Float64Array([0, 1, 0]),
+        accelerations: new Float64Array(3)
+    };
+    return {
+        block,
+        getIntegrationState: () => [block],
+        computeAccelerations() {
+            const [x, y, z] = block.positions;
+            const r3 = Math.hypot(x, y, z) ** 3;
+            block.accelerations[0] = -x / r3;
+            block.accelerations[1] = -y / r3;
+            block.accelerations[2] = -z / r3;
+        }
+    };
+}
+
+function energy({ positions, velocities }) {
+    const [x, y, z] = positions;
+    const [vx, vy, vz] = velocities;
+    return 0.5 * (vx * vx + vy * vy + vz * vz) - 1 / Math.hypot(x, y, z);
+}
+
+// Largest relative energy error over ten orbits at 64 steps per orbit
+function energyDrift(integrator) {
+    const system = createOrbit();
+    const initial = energy(system.block);
+    const dt = 2 * Math.PI / 64;
+    let worst = 0;
+    for (let i = 0; i < 640; i++) {
+        integrator.step(system, dt);
+        worst = Math.max(worst, Math.abs(energy(system.block) - initial) / Math.abs(initial));
+    }
+    return worst;
+}
+
+const TOLERANCES = {
+    symplecticEuler: 0.2,
+    velocityVerlet: 5e-3,
+    leapfrog: 5e-3,
+    rk4: 1e-4
+};
+
+for (const [name, integrator] of Object.entries(integrators)) {
+    test(`${name} keeps the orbit's energy`, () => {
+        assert.ok(energyDrift(integrator) < TOLERANCES[name], `drift ${energyDrift(integrator)}`);
+    });
+}
+
+test('symplectic schemes don\'t drift away over many orbits', () => {
+    for (const name of ['symplecticEuler', 'velocityVerlet', 'leapfrog']) {
+        const system = createOrbit();
+        const initial = energy(system.block);
+        const dt = 2 * Math.PI / 64;
+        // Largest energy error over the next ten orbits
+        const worstOver = () => {
+            let worst = 0;
+            for (let i = 0; i < 640; i++) {
+                integrators[name].step(system, dt);
+                worst = Math.max(worst, Math.abs(energy(system.block) - initial));
+            }
+            return worst;
+        };
+        const early = worstOver();
+        for (let orbit = 10; orbit < 90; orbit++) {
+            for (let i = 0; i < 64; i++) integrators[name].step(system, dt);
+        }
+        const late = worstOver();
+        assert.ok(late < 1.5 * early, `${name}: ${early} then ${late}`);
+    }
+});
+
+test('unknown integrator names fall back to leapfrog', () => {
+    assert.equal(getIntegrator('rk4'), integrators.rk4);
+    assert.equal(getIntegrator('nonsense'), leapfrog);
+});
@@ -0,0 +1,57 @@
+import { test } from 'node:test';
+import assert from 'node:assert/strict';
+import { solveKepler, orbitalPosition, orbitalVelocity } from '../js/KeplerOrbit.js';
+
+const elements = {
+    semiMajorAxis: 10,
+    eccentricity: 0.4,
+    inclination: 0.3,
+    argumentOfPeriapsis: 1.1,
+    ascendingNode: -0.7
+};
+
+const length = v => Math.hypot(v.x, v.y, v.z);
+
+test('solveKepler inverts Kepler\'s equation', () => {
+    for (const eccentricity of [0, 0.3, 0.7, 0.95]) {
+        for (let meanAnomaly = -7; meanAnomaly <= 7; meanAnomaly += 0.37) {
+            const E = solveKepler(meanAnomaly, eccentricity);
+            const roundTrip = E - eccentricity * Math.sin(E);
+            const wrapped = ((meanAnomaly % (2 * Math.PI)) + 2 * Math.PI) % (2 * Math.PI);
+            assert.ok(Math.abs(roundTrip - wrapped) < 1e-10, `e = ${eccentricity}, M = ${meanAnomaly}`);
+        }
+    }
+});
+
+test('periapsis and apoapsis sit at a(1 - e) and a(1 + e)', () => {
+    const { semiMajorAxis: a, eccentricity: e } = elements;
+    assert.ok(Math.abs(length(orbitalPosition(elements, 0)) - a * (1 - e)) < 1e-9);
+    assert.ok(Math.abs(length(orbitalPosition(elements, Math.PI)) - a * (1 + e)) < 1e-9);
+});
+
+test('velocity is the derivative of position and obeys vis-viva', () => {
+    const meanMotion = 0.5;
+    const { semiMajorAxis: a } = elements;
+    const mu = meanMotion * meanMotion * a * a * a;
+    const h = 1e-6;
+
+    for (const meanAnomaly of [0, 0.8, 2.5, 4.0]) {
+        const before = orbitalPosition(elements, meanAnomaly - meanMotion * h);
+        const after = orbitalPosition(elements, meanAnomaly + meanMotion * h);
+        const velocity = orbitalVelocity(elements, meanAnomaly, meanMotion);
+        for (const axis of ['x', 'y', 'z']) {
+            assert.ok(Math.abs((after[axis] - before[axis]) / (2 * h) - velocity[axis]) < 1e-5);
+        }
+
+        const r = length(orbitalPosition(elements, meanAnomaly));
+        const speedSq = length(velocity) ** 2;
+        assert.ok(Math.abs(speedSq - mu * (2 / r - 1 / a)) < 1e-9 * mu);
+    }
+});
+
+test('zero inclination keeps the orbit in the XZ plane', () => {
+    const flat = { ...elements, inclination: 0 };
+    for (const meanAnomaly of [0, 1, 2, 3]) {
+        assert.ok(Math.abs(orbitalPosition(flat, meanAnomaly).y) < 1e-12);
+    }
+});
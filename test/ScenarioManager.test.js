@@ -0,0 +1,78 @@
+import { test } from 'node:test';
+import assert from 'node:assert/strict';
+import { ScenarioManager } from '../js/ScenarioManager.js';
+
+const manager = new ScenarioManager();
+const planet = { id: 'planet', type: 'planet', mass: 1000, radius: 5 };
+const moon = { id: 'moon', type: 'moon', mass: 10, radius: 1, orbit: { parent: 'planet', semiMajorAxis: 15 } };
+
+test('scenarios survive encoding', () => {
+    const probes = [{ name: 'harbor', latitude: 10, longitude: 20 }];
+    const seed = manager.createScenario({ viscosity: 2 }, [planet, moon], [], 'fixed', probes);
+    assert.deepEqual(manager.decodeSeed(seed), {
+        seed: 'fixed',
+        parameters: { viscosity: 2 },
+        objects: [planet, moon],
+        forces: [],
+        probes
+    });
+    assert.throws(() => manager.decodeSeed('not base64 json'), /Invalid scenario seed/);
+});
+
+test('a sweep grid takes every combination', () => {
+    const runs = manager.createSweep({ seed: 'base', parameters: { viscosity: 1, damping: 0.3 } },
+        { grid: { moonMass: [50, 100, 200], viscosity: [0.5, 2] } });
+
+    assert.equal(runs.length, 6);
+    assert.deepEqual(runs.map(run => run.values), [
+        { moonMass: 50, viscosity: 0.5 }, { moonMass: 50, viscosity: 2 },
+        { moonMass: 100, viscosity: 0.5 }, { moonMass: 100, viscosity: 2 },
+        { moonMass: 200, viscosity: 0.5 }, { moonMass: 200, viscosity: 2 }
+    ]);
+    const scenario = manager.decodeSeed(runs[3].scenario);
+    assert.deepEqual(scenario.parameters, { viscosity: 2, damping: 0.3, moonMass: 100 });
+    assert.equal(scenario.seed, 'base');
+});
+
+test('a sweep list gives explicit sets and replicates get their own seeds', () => {
+    const runs = manager.createSweep({ seed: 'base' }, { list: [{ moonMass: 1 }, { viscosity: 3 }], replicates: 3 });
+
+    assert.equal(runs.length, 6);
+    assert.deepEqual(runs.map(run => [run.run, run.replicate]), [[0, 0], [0, 1], [0, 2], [1, 0], [1, 1], [1, 2]]);
+    const seeds = runs.map(run => manager.decodeSeed(run.scenario).seed);
+    assert.deepEqual(seeds, ['base-0', 'base-1', 'base-2', 'base-0', 'base-1', 'base-2']);
+    // The same definition gives the same ensemble
+    assert.deepEqual(manager.createSweep({ seed: 'base' }, { list: [{ moonMass: 1 }, { viscosity: 3 }], replicates: 3 }), runs);
+});
+
+test('sweep values must be non-empty lists', () => {
+    assert.throws(() => manager.createSweep({}, { grid: { viscosity: [] } }), /viscosity must be a non-empty array/);
+    assert.throws(() => manager.createSweep({}, { grid: { viscosity: 2 } }), /non-empty array/);
+});
+
+test('objects are validated in order', () => {
+    manager.validateObjects([planet, moon]);
+    assert.throws(() => manager.validateObjects([moon, planet]), /parent must be an earlier object/);
+    assert.throws(() => manager.validateObjects([planet, { ...planet }]), /duplicate id/);
+    assert.throws(() => manager.validateObjects([{ ...planet, type: 'comet' }]), /type must be one of/);
+    assert.throws(() => manager.validateObjects([{ ...planet, mass: 0 }]), /mass and radius must be positive/);
+    assert.throws(() => manager.validateObjects([planet, { ...moon, orbit: { ...moon.orbit, eccentricity: 1 } }]),
+        /eccentricity must be in \[0, 1\)/);
+});
+
+test('forces are validated against their field types', () => {
+    manager.validateForces([{ type: 'uniform', direction: [0, 1, 0], strength: 2 }]);
+    assert.throws(() => manager.validateForces([{ type: 'tornado' }]), /unknown type "tornado"/);
+    assert.throws(() => manager.validateForces([{ type: 'uniform', direction: [0, 1] }]), /direction must be a vector/);
+    assert.throws(() => manager.validateForces([{ type: 'uniform', strength: 'high' }]), /strength must be a number/);
+});
+
+test('probes are validated', () => {
+    manager.validateProbes([{ name: 'a', latitude: -90, longitude: 400, radius: 2 }]);
+    assert.throws(() => manager.validateProbes([{ name: '', latitude: 0, longitude: 0 }]), /name must be a non-empty string/);
+    assert.throws(() => manager.validateProbes([
+        { name: 'a', latitude: 0, longitude: 0 }, { name: 'a', latitude: 1, longitude: 1 }
+    ]), /duplicate name/);
+    assert.throws(() => manager.validateProbes([{ name: 'a', latitude: 91, longitude: 0 }]), /latitude/);
+    assert.throws(() => manager.validateProbes([{ name: 'a', latitude: 0, longitude: 0, radius: 0 }]), /radius/);
+});
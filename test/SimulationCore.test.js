@@ -0,0 +1,114 @@
+// Headless checks of the simulation core, run with `npm test`
+import { test } from 'node:test';
+import assert from 'node:assert/strict';
+import { SimulationCore } from '../js/SimulationCore.js';
+import { measureDiagnostics } from '../js/Diagnostics.js';
+
+const PARTICLES = 200;
+const STEPS = 120;
+
+function createCore(seed) {
+    const core = new SimulationCore();
+    core.setParameter('particleCount', PARTICLES);
+    core.setSeed(seed);
+    core.initializeParticles();
+    return core;
+}
+
+function run(core, steps) {
+    for (let i = 0; i < steps; i++) {
+        core.step(core.parameters.fixedTimeStep);
+    }
+}
+
+test('the same seed gives the same run', () => {
+    const a = createCore('determinism');
+    const b = createCore('determinism');
+    run(a, STEPS);
+    run(b, STEPS);
+
+    assert.equal(a.simulationTime, b.simulationTime);
+    assert.deepEqual(a.particles.positions, b.particles.positions);
+    assert.deepEqual(a.particles.velocities, b.particles.velocities);
+});
+
+test('a different seed gives a different layout', () => {
+    const a = createCore('one');
+    const b = createCore('two');
+
+    assert.notDeepEqual(a.particles.positions, b.particles.positions);
+});
+
+test('particles keep a positive mass and their count', () => {
+    const core = createCore('mass');
+    const mass = core.particleMass;
+    assert.ok(mass > 0 && Number.isFinite(mass));
+
+    run(core, STEPS);
+
+    assert.equal(core.particles.count, PARTICLES);
+    assert.equal(core.particleMass, mass);
+    for (const value of core.particles.positions.subarray(0, PARTICLES * 3)) {
+        assert.ok(Number.isFinite(value));
+    }
+});
+
+test('energy stays finite and never grows while the layer settles', () => {
+    // Without the moon nothing does work on the fluid; viscosity, damping
+    // and surface contact only take energy out
+    const core = new SimulationCore();
+    core.setParameter('particleCount', PARTICLES);
+    core.setParameter('moonMass', 0);
+    core.setSeed('energy');
+    core.initializeParticles();
+
+    const initial = measureDiagnostics(core).totalEnergy;
+    assert.ok(Number.isFinite(initial));
+
+    for (let i = 0; i < 4; i++) {
+        run(core, STEPS / 2);
+        const energy = measureDiagnostics(core).totalEnergy;
+        assert.ok(Number.isFinite(energy));
+        assert.ok(energy <= initial + 1e-6 * Math.abs(initial), `energy rose from ${initial} to ${energy}`);
+        assert.ok(Math.abs(energy - initial) < 0.1 * Math.abs(initial), `energy drifted from ${initial} to ${energy}`);
+    }
+});
+
+test('substeps held at the adaptive cap are counted', () => {
+    const core = createCore('clamped');
+    run(core, 10);
+    assert.equal(core.clampedSubsteps, 0);
+
+    // A stiff fluid whose steps may not be split at all
+    core.setParameter('pressureStiffness', 1e6);
+    core.setParameter('maxAdaptiveSubsteps', 1);
+    run(core, 10);
+    assert.equal(core.clampedSubsteps, 10);
+    assert.equal(measureDiagnostics(core).clampedSubsteps, 10);
+});
+
+test('gravityMultiplier strengthens the pull on the fluid, not on other bodies', () => {
+    const core = createCore('multiplier');
+    core.setParameter('orbitMode', 'nbody');
+    const { primaryBody: planet, primaryMoon: moon } = core;
+    assert.ok(moon.gravityMultiplier > 1);
+
+    core.computeBodyAccelerations();
+    const distance = planet.position.distanceTo(moon.position);
+    const expected = core.scaledGravitationalConstant() * moon.mass / (distance * distance);
+    assert.ok(Math.abs(planet.acceleration.length() - expected) < 1e-9 * expected);
+});
+
+test('overlapping surfaces still leave the fluid a positive mass', () => {
+    const core = new SimulationCore();
+    core.setParameter('particleCount', 20);
+    core.setBodies([
+        { id: 'a', type: 'planet', mass: 1000, radius: 5, fluid: true },
+        { id: 'b', type: 'planet', mass: 1000, radius: 5, position: [0.01, 0, 0] },
+        { id: 'c', type: 'planet', mass: 1000, radius: 5, position: [0, 0.01, 0] }
+    ]);
+    core.setParameter('fluidHeight', 0.05);
+    core.initializeParticles();
+
+    assert.ok(core.particleMass > 0 && Number.isFinite(core.particleMass));
+});
@@ -0,0 +1,89 @@
+import { test } from 'node:test';
+import assert from 'node:assert/strict';
+import { SpatialHash } from '../js/SpatialHash.js';
+import { SeededRandom } from '../js/SeededRandom.js';
+
+const COUNT = 300;
+const CUTOFF = 1.0;
+
+function randomPositions(seed) {
+    const random = new SeededRandom(seed);
+    const positions = new Float64Array(COUNT * 3);
+    for (let k = 0; k < positions.length; k++) {
+        positions[k] = random.next() * 6 - 3;
+    }
+    return positions;
+}
+
+// Sorted neighbor indices of every particle, from the compressed lists
+function neighborLists(hash) {
+    const lists = [];
+    for (let i = 0; i < hash.count; i++) {
+        lists.push(Array.from(hash.indices.subarray(hash.starts[i], hash.ends[i])).sort((a, b) => a - b));
+    }
+    return lists;
+}
+
+function bruteForce(positions) {
+    const lists = [];
+    for (let i = 0; i < COUNT; i++) {
+        const list = [];
+        for (let j = 0; j < COUNT; j++) {
+            if (j === i) continue;
+            const distance = Math.hypot(positions[i * 3] - positions[j * 3],
+                positions[i * 3 + 1] - positions[j * 3 + 1], positions[i * 3 + 2] - positions[j * 3 + 2]);
+            if (distance < CUTOFF) list.push(j);
+        }
+        lists.push(list);
+    }
+    return lists;
+}
+
+test('neighbor lists match a brute-force search', () => {
+    const positions = randomPositions('hash');
+    // Cells smaller than the cutoff so every query scans many buckets
+    const hash = new SpatialHash().build(positions, COUNT, CUTOFF / 3).buildNeighborLists(CUTOFF);
+
+    assert.deepEqual(neighborLists(hash), bruteForce(positions));
+});
+
+test('distances are reported with their neighbors', () => {
+    const positions = randomPositions('distances');
+    const hash = new SpatialHash().build(positions, COUNT, CUTOFF).buildNeighborLists(CUTOFF);
+
+    for (let i = 0; i < COUNT; i++) {
+        for (let s = hash.starts[i]; s < hash.ends[i]; s++) {
+            const j = hash.indices[s];
+            const distance = Math.hypot(positions[i * 3] - positions[j * 3],
+                positions[i * 3 + 1] - positions[j * 3 + 1], positions[i * 3 + 2] - positions[j * 3 + 2]);
+            assert.ok(Math.abs(hash.distances[s] - distance) < 1e-12);
+        }
+    }
+});
+
+test('a subset build finds the same neighbors for its members', () => {
+    const positions = randomPositions('subset');
+    const members = Int32Array.from({ length: COUNT }, (_, i) => i).filter(i => positions[i * 3] < 1);
+    const full = neighborLists(new SpatialHash().build(positions, COUNT, CUTOFF).buildNeighborLists(CUTOFF));
+    const hash = new SpatialHash().build(positions, COUNT, CUTOFF, members, members.length);
+    const queries = members.filter(i => positions[i * 3] < 0);
+    hash.buildNeighborLists(CUTOFF, queries, queries.length);
+
+    for (const i of queries) {
+        const list = Array.from(hash.indices.subarray(hash.starts[i], hash.ends[i])).sort((a, b) => a - b);
+        assert.deepEqual(list, full[i]);
+    }
+});
+
+test('query stamps wrap without scanning buckets twice', () => {
+    const positions = randomPositions('wrap');
+    const hash = new SpatialHash().build(positions, COUNT, CUTOFF / 3);
+    const expected = neighborLists(hash.buildNeighborLists(CUTOFF));
+
+    // Run the stamp counter past the Int32 limit mid-build
+    hash.queryStamp = 0x7fffffff - COUNT / 2;
+    const wrapped = neighborLists(hash.buildNeighborLists(CUTOFF));
+
+    assert.deepEqual(wrapped, expected);
+    assert.ok(hash.queryStamp < COUNT);
+});
@@ -0,0 +1,87 @@
+import { test } from 'node:test';
+import assert from 'node:assert/strict';
+import { Vector3 } from 'three';
+import { SimulationCore } from '../js/SimulationCore.js';
+import { TideGauge, tideGaugeCsv } from '../js/TideGauges.js';
+
+const DEGREES = Math.PI / 180;
+
+// A core whose particles sit at the given heights above points of its
+// primary body's surface, as [latitude, longitude, height] in degrees
+function coreWithParticles(placements) {
+    const core = new SimulationCore();
+    core.setParameter('particleCount', placements.length);
+    const body = core.primaryBody;
+    const direction = new Vector3();
+    placements.forEach(([latitude, longitude, height], i) => {
+        body.surfaceDirection(latitude * DEGREES, longitude * DEGREES, direction);
+        const position = direction.multiplyScalar(body.radius + height).add(body.position);
+        core.particles.setPosition(i, position.x, position.y, position.z);
+    });
+    return core;
+}
+
+test('a gauge reads the mean height of its column', () => {
+    const core = coreWithParticles([
+        [0, 0, 0.1], [0, 0, 0.2], [0, 0, 0.3],
+        [0, 90, 0.4], [45, 180, 0.8]
+    ]);
+    core.setProbes([{ name: 'here', latitude: 0, longitude: 0 }, { name: 'dry', latitude: -60, longitude: 0 }]);
+    core.recordTideGauges();
+
+    const [here, dry] = core.tideGauges;
+    assert.ok(Math.abs(here.heights[0] - 0.2) < 1e-6);
+    assert.equal(dry.heights[0], 0);
+    assert.equal(here.times[0], core.simulationTime);
+});
+
+test('spray above a gap in the column is not counted', () => {
+    const core = coreWithParticles([[0, 0, 0.1], [0, 0, 0.3], [0, 0, 4.0]]);
+    core.setProbes([{ name: 'here', latitude: 0, longitude: 0 }]);
+    core.recordTideGauges();
+
+    assert.ok(Math.abs(core.tideGauges[0].heights[0] - 0.2) < 1e-6);
+});
+
+test('the gauge radius sets how far along the surface the column reaches', () => {
+    // About 1.2 surface units from the gauge on a radius 5 body
+    const core = coreWithParticles([[0, 0, 0.1], [0, 14, 0.5]]);
+    core.setProbes([
+        { name: 'narrow', latitude: 0, longitude: 0, radius: 1 },
+        { name: 'wide', latitude: 0, longitude: 0, radius: 1.5 }
+    ]);
+    core.recordTideGauges();
+
+    const [narrow, wide] = core.tideGauges;
+    assert.ok(Math.abs(narrow.heights[0] - 0.1) < 1e-6);
+    assert.ok(Math.abs(wide.heights[0] - 0.3) < 1e-6);
+});
+
+test('the CSV quotes names that would break a row', () => {
+    const core = new SimulationCore();
+    core.setProbes([
+        { name: 'plain', latitude: 0, longitude: 0 },
+        { name: 'say "hi", ok', latitude: 10, longitude: 0 },
+        { name: 'two\nlines', latitude: 20, longitude: 0 }
+    ]);
+    const [plain, quoted, multiline] = core.tideGauges;
+    for (const gauge of core.tideGauges) {
+        gauge.record(0.5, 0.25);
+    }
+
+    assert.equal(tideGaugeCsv([plain, quoted, multiline]),
+        'time,plain,"say ""hi"", ok","two\nlines"\n0.5,0.25,0.25,0.25\n');
+});
+
+test('gauges drop their oldest samples together beyond the limit', () => {
+    const gauges = [new TideGauge({ name: 'a', latitude: 0, longitude: 0 }, 3),
+        new TideGauge({ name: 'b', latitude: 0, longitude: 0 }, 3)];
+    for (let step = 0; step < 4; step++) {
+        gauges.forEach((gauge, index) => gauge.record(step, step + index));
+    }
+    gauges.forEach(gauge => gauge.append({ times: [4, 5], heights: [0, 0] }));
+
+    assert.equal(tideGaugeCsv(gauges), 'time,a,b\n3,3,4\n4,0,0\n5,0,0\n');
+    assert.deepEqual(gauges[0].drain(), { times: [3, 4, 5], heights: [3, 0, 0] });
+    assert.deepEqual(gauges[0].times, []);
+});
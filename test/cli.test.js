@@ -0,0 +1,65 @@
+import { test } from 'node:test';
+import assert from 'node:assert/strict';
+import { mkdtempSync, writeFileSync, rmSync } from 'node:fs';
+import { tmpdir } from 'node:os';
+import { join } from 'node:path';
+import { parseArguments, parseThreads, readScenario } from '../js/cli.js';
+import { parseArguments as parseSweepArguments } from '../js/sweep.js';
+import { ScenarioManager } from '../js/ScenarioManager.js';
+
+test('run options are parsed into numbers', () => {
+    const options = parseArguments(['--scenario', 'seed', '--steps', '500', '--snapshot-every', '50',
+        '--threads', '3', '--quiet']);
+
+    assert.deepEqual(options, {
+        scenario: 'seed', steps: 500, snapshotEvery: 50, threads: 3,
+        out: 'output', every: 10, quiet: true, help: false
+    });
+    assert.equal(parseArguments([]).threads, undefined);
+    assert.equal(parseArguments(['-h']).help, true);
+});
+
+test('bad run options are reported', () => {
+    assert.throws(() => parseArguments(['--stpes', '10']), /Unknown option: --stpes/);
+    assert.throws(() => parseArguments(['--steps']), /Missing value for --steps/);
+    assert.throws(() => parseArguments(['--steps', '0']), /--steps must be a positive number/);
+    assert.throws(() => parseArguments(['--snapshot-every', 'often']), /--snapshot-every must be a positive number/);
+});
+
+test('thread counts must be whole and not negative', () => {
+    assert.equal(parseThreads('0'), 0);
+    assert.equal(parseThreads('8'), 8);
+    assert.throws(() => parseThreads('1.5'), /--threads must be a whole number/);
+    assert.throws(() => parseThreads('-1'), /--threads must be a whole number/);
+    assert.throws(() => parseThreads('many'), /--threads must be a whole number/);
+});
+
+test('sweep options are parsed into numbers', () => {
+    const options = parseSweepArguments(['--sweep', 'moon.json', '--seconds', '2.5', '--keep-runs', '--threads', '0']);
+
+    assert.deepEqual(options, {
+        sweep: 'moon.json', seconds: 2.5, threads: 0,
+        out: join('output', 'sweep'), every: 10, keepRuns: true, quiet: false, help: false
+    });
+    assert.throws(() => parseSweepArguments(['--steps', 'x']), /--steps must be a positive number/);
+    assert.throws(() => parseSweepArguments(['--out']), /Missing value for --out/);
+    assert.throws(() => parseSweepArguments(['--snapshot-every', '5']), /Unknown option/);
+});
+
+test('scenarios are read from seeds and from files', (t) => {
+    const manager = new ScenarioManager();
+    const seed = manager.createScenario({ viscosity: 2 }, [], [], 'fixed', []);
+    assert.deepEqual(readScenario(seed), { scenario: manager.decodeSeed(seed), seed });
+
+    const directory = mkdtempSync(join(tmpdir(), 'tides-cli-'));
+    t.after(() => rmSync(directory, { recursive: true, force: true }));
+    const file = join(directory, 'scenario.json');
+    writeFileSync(file, JSON.stringify({ parameters: { damping: 0.1 } }));
+    assert.deepEqual(readScenario(file), {
+        scenario: { parameters: { damping: 0.1 }, objects: [], forces: [], probes: [] },
+        seed: file
+    });
+
+    writeFileSync(file, JSON.stringify({ seed: 'named', probes: [{ name: 'a', latitude: 100, longitude: 0 }] }));
+    assert.throws(() => readScenario(file), /latitude/);
+});
@@ -1,6 +1,20 @@
+// SharedArrayBuffer, which the force worker pool needs, is only available
+// to cross-origin isolated pages
+const crossOriginIsolation = {
+  'Cross-Origin-Opener-Policy': 'same-origin',
+  'Cross-Origin-Embedder-Policy': 'require-corp'
+};
+
 export default {
   server: {
     port: 3000,
-    open: false // Disable automatic browser opening
-  }
+    open: false, // Disable automatic browser opening
+    headers: crossOriginIsolation
+  },
+  preview: {
+    headers: crossOriginIsolation
+  },
+  worker: {
+    format: 'es' // The simulation worker is an ES module with imports
   }
+}